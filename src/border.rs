@@ -0,0 +1,378 @@
+use std::ops::DerefMut;
+
+use image::{GenericImageView, ImageBuffer, Pixel};
+
+/// Describes how coordinates outside of the image are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BorderMode<P> {
+    /// Clamps the coordinates to the nearest edge pixel.
+    Clamp,
+    /// Wraps the coordinates around, tiling the image.
+    Wrap,
+    /// Mirrors the coordinates at the edges, repeating the edge pixel (`cba|abc|cba`).
+    Reflect,
+    /// Uses the given pixel for any coordinates outside of the image.
+    Constant(P),
+}
+
+impl<P: Copy> BorderMode<P> {
+    /// Resolves the coordinates into the bounds of an image with the given dimensions,
+    /// or returns the constant pixel for coordinates outside of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions are empty, unless the mode is [`BorderMode::Constant`].
+    pub(crate) fn resolve(
+        self,
+        x: i64,
+        y: i64,
+        (width, height): (u32, u32),
+    ) -> Result<(u32, u32), P> {
+        let axis_mode = match self {
+            BorderMode::Clamp => AxisBorderMode::Clamp,
+            BorderMode::Wrap => AxisBorderMode::Wrap,
+            BorderMode::Reflect => AxisBorderMode::Reflect,
+            BorderMode::Constant(pixel) => {
+                return match (u32::try_from(x), u32::try_from(y)) {
+                    (Ok(x), Ok(y)) if x < width && y < height => Ok((x, y)),
+                    _ => Err(pixel),
+                }
+            }
+        };
+
+        assert!(width > 0 && height > 0, "image is empty");
+        Ok((axis_mode.resolve(x, width), axis_mode.resolve(y, height)))
+    }
+}
+
+/// Describes how out-of-bounds values are resolved along a single axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisBorderMode {
+    /// Clamps the value to the nearest edge.
+    Clamp,
+    /// Wraps the value around the axis.
+    Wrap,
+    /// Mirrors the value at the edges, repeating the edge value.
+    Reflect,
+}
+
+impl AxisBorderMode {
+    /// Resolves the axis value into `[0, len)`.
+    #[inline]
+    pub(crate) fn resolve(self, value: i64, len: u32) -> u32 {
+        match self {
+            AxisBorderMode::Clamp => clamp_axis(value, len),
+            AxisBorderMode::Wrap => wrap_axis(value, len),
+            AxisBorderMode::Reflect => reflect_axis(value, len),
+        }
+    }
+}
+
+/// Clamps the axis value into `[0, len)`.
+#[inline]
+pub(crate) fn clamp_axis(value: i64, len: u32) -> u32 {
+    value.clamp(0, len as i64 - 1) as u32
+}
+
+/// Wraps the axis value into `[0, len)`.
+#[inline]
+pub(crate) fn wrap_axis(value: i64, len: u32) -> u32 {
+    value.rem_euclid(len as i64) as u32
+}
+
+/// Reflects the axis value into `[0, len)`, repeating the edge values.
+#[inline]
+pub(crate) fn reflect_axis(value: i64, len: u32) -> u32 {
+    let period = 2 * len as i64;
+    let value = value.rem_euclid(period);
+    if value < len as i64 {
+        value as u32
+    } else {
+        (period - 1 - value) as u32
+    }
+}
+
+/// Returns the coordinates mirrored at the image edges into the image bounds, see [`BorderMode::Reflect`].
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+#[inline]
+pub fn reflect_coordinate<I: GenericImageView>(image: &I, x: i32, y: i32) -> (u32, u32) {
+    let (width, height) = image.dimensions();
+    (
+        reflect_axis(x as i64, width),
+        reflect_axis(y as i64, height),
+    )
+}
+
+/// Returns the coordinates wrapped around into the image bounds, see [`BorderMode::Wrap`].
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+#[inline]
+pub fn wrap_coordinate<I: GenericImageView>(image: &I, x: i32, y: i32) -> (u32, u32) {
+    let (width, height) = image.dimensions();
+    (wrap_axis(x as i64, width), wrap_axis(y as i64, height))
+}
+
+/// Returns the pixel at the given coordinates, resolving out-of-bounds coordinates with the border mode.
+///
+/// # Panics
+///
+/// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+pub fn get_pixel_border<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+    mode: BorderMode<I::Pixel>,
+) -> I::Pixel {
+    match mode.resolve(x as i64, y as i64, image.dimensions()) {
+        Ok((x, y)) => unsafe { image.unsafe_get_pixel(x, y) },
+        Err(pixel) => pixel,
+    }
+}
+
+/// Calls `f` with the offset and the pixel of every position in the square neighbourhood
+/// of the given `radius` around the center, resolving out-of-bounds positions with the border mode.
+///
+/// The `(2 * radius + 1)^2` positions are visited in row-major order without allocating.
+///
+/// # Panics
+///
+/// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+pub fn for_each_neighbor<I, F>(
+    image: &I,
+    cx: i32,
+    cy: i32,
+    radius: u32,
+    mode: BorderMode<I::Pixel>,
+    mut f: F,
+) where
+    I: GenericImageView,
+    F: FnMut(i32, i32, I::Pixel),
+{
+    debug_assert!(radius <= i32::MAX as u32, "radius does not fit in i32");
+
+    let dimensions = image.dimensions();
+    let radius = radius as i64;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let pixel = match mode.resolve(cx as i64 + dx, cy as i64 + dy, dimensions) {
+                Ok((x, y)) => unsafe { image.unsafe_get_pixel(x, y) },
+                Err(pixel) => pixel,
+            };
+            f(dx as i32, dy as i32, pixel);
+        }
+    }
+}
+
+/// Returns the pixel at the given `i64` coordinates wrapped around into the image bounds.
+///
+/// The wrapping is computed in `i64`, so coordinates beyond the `i32` range do not overflow.
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+#[inline]
+pub fn get_pixel_wrapped_i64<I: GenericImageView>(image: &I, x: i64, y: i64) -> I::Pixel {
+    let (width, height) = image.dimensions();
+    assert!(width > 0 && height > 0, "image is empty");
+    unsafe { image.unsafe_get_pixel(wrap_axis(x, width), wrap_axis(y, height)) }
+}
+
+/// Returns a mutable reference to the pixel at the given coordinates wrapped around into
+/// the image bounds, so edits near an edge of a tileable image continue on the opposite edge.
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+#[inline]
+pub fn get_pixel_wrapped_mut<P, C>(image: &mut ImageBuffer<P, C>, x: i32, y: i32) -> &mut P
+where
+    P: Pixel,
+    C: DerefMut<Target = [P::Subpixel]>,
+{
+    let (width, height) = image.dimensions();
+    assert!(width > 0 && height > 0, "image is empty");
+    image.get_pixel_mut(wrap_axis(x as i64, width), wrap_axis(y as i64, height))
+}
+
+/// Returns the pixel at the given coordinates, resolving each axis with its own border mode.
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+pub fn get_pixel_modes<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+    x_mode: AxisBorderMode,
+    y_mode: AxisBorderMode,
+) -> I::Pixel {
+    let (width, height) = image.dimensions();
+    assert!(width > 0 && height > 0, "image is empty");
+
+    let x = x_mode.resolve(x as i64, width);
+    let y = y_mode.resolve(y as i64, height);
+    unsafe { image.unsafe_get_pixel(x, y) }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma};
+
+    use super::*;
+
+    #[test]
+    fn axis_resolution() {
+        let clamped: Vec<_> = (-3..6).map(|v| clamp_axis(v, 3)).collect();
+        assert_eq!(clamped, [0, 0, 0, 0, 1, 2, 2, 2, 2]);
+
+        let wrapped: Vec<_> = (-3..6).map(|v| wrap_axis(v, 3)).collect();
+        assert_eq!(wrapped, [0, 1, 2, 0, 1, 2, 0, 1, 2]);
+
+        let reflected: Vec<_> = (-3..6).map(|v| reflect_axis(v, 3)).collect();
+        assert_eq!(reflected, [2, 1, 0, 0, 1, 2, 2, 1, 0]);
+    }
+
+    #[test]
+    fn neighbors_visit_square_offsets() {
+        let image = GrayImage::from_fn(3, 3, |x, y| Luma([(y * 3 + x) as u8]));
+
+        for radius in 0..4 {
+            let mut visited = vec![];
+            for_each_neighbor(&image, 1, 1, radius, BorderMode::Clamp, |dx, dy, _| {
+                visited.push((dx, dy))
+            });
+
+            let r = radius as i32;
+            let expected: Vec<_> = (-r..=r)
+                .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+                .collect();
+            assert_eq!(visited.len(), (2 * radius as usize + 1).pow(2));
+            assert_eq!(visited, expected);
+        }
+
+        let mut pixels = vec![];
+        for_each_neighbor(
+            &image,
+            0,
+            0,
+            1,
+            BorderMode::Constant(Luma([9])),
+            |_, _, p| pixels.push(p[0]),
+        );
+        assert_eq!(pixels, [9, 9, 9, 9, 0, 1, 9, 3, 4]);
+    }
+
+    #[test]
+    fn wrapped_mut_edits_opposite_edge() {
+        let mut image = GrayImage::new(3, 2);
+
+        *get_pixel_wrapped_mut(&mut image, -1, -1) = Luma([7]);
+        assert_eq!(image.get_pixel(2, 1), &Luma([7]));
+
+        get_pixel_wrapped_mut(&mut image, 3, 4).0[0] = 9;
+        assert_eq!(image.get_pixel(0, 0), &Luma([9]));
+        assert_eq!(image.as_raw(), &[9, 0, 0, 0, 0, 7]);
+    }
+
+    #[test]
+    fn reflect_and_wrap_coordinates() {
+        let image = GrayImage::new(4, 3);
+
+        assert_eq!(reflect_coordinate(&image, -1, 3), (0, 2));
+        assert_eq!(reflect_coordinate(&image, 5, -2), (2, 1));
+        assert_eq!(wrap_coordinate(&image, -1, -1), (3, 2));
+        assert_eq!(wrap_coordinate(&image, 4, 7), (0, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrap_coordinate_for_empty_image() {
+        wrap_coordinate(&GrayImage::new(0, 0), 0, 0);
+    }
+
+    #[test]
+    fn get_pixel_wrapped_beyond_i32() {
+        let image = GrayImage::from_fn(7, 2, |x, y| Luma([(y * 7 + x) as u8]));
+
+        // i64::MAX is a multiple of 7, so i64::MIN + 1 wraps to column 0
+        assert_eq!(get_pixel_wrapped_i64(&image, i64::MIN + 1, 0).0, [0]);
+        assert_eq!(get_pixel_wrapped_i64(&image, i64::MAX, -1).0, [7]);
+        assert_eq!(get_pixel_wrapped_i64(&image, -1, 2).0, [6]);
+    }
+
+    #[test]
+    fn get_pixel_border_modes() {
+        let image = GrayImage::from_vec(3, 1, vec![10, 20, 30]).unwrap();
+        let get = |x, mode| get_pixel_border(&image, x, 0, mode).0[0];
+
+        assert_eq!(get(1, BorderMode::Clamp), 20);
+        assert_eq!(get(-1, BorderMode::Clamp), 10);
+        assert_eq!(get(3, BorderMode::Clamp), 30);
+
+        assert_eq!(get(-1, BorderMode::Wrap), 30);
+        assert_eq!(get(3, BorderMode::Wrap), 10);
+
+        assert_eq!(get(-1, BorderMode::Reflect), 10);
+        assert_eq!(get(-2, BorderMode::Reflect), 20);
+        assert_eq!(get(4, BorderMode::Reflect), 20);
+
+        assert_eq!(get(-1, BorderMode::Constant(Luma([0]))), 0);
+        assert_eq!(get(2, BorderMode::Constant(Luma([0]))), 30);
+    }
+
+    #[test]
+    fn get_pixel_border_for_empty_image() {
+        let image = GrayImage::new(0, 0);
+        let pixel = get_pixel_border(&image, 0, 0, BorderMode::Constant(Luma([7])));
+        assert_eq!(pixel.0, [7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_pixel_border_clamp_for_empty_image() {
+        let image = GrayImage::new(0, 0);
+        get_pixel_border(&image, 0, 0, BorderMode::Clamp);
+    }
+
+    #[test]
+    fn get_pixel_modes_per_axis() {
+        let image = GrayImage::from_fn(3, 2, |x, y| Luma([(y * 3 + x) as u8]));
+        let (wrap, clamp) = (AxisBorderMode::Wrap, AxisBorderMode::Clamp);
+
+        assert_eq!(get_pixel_modes(&image, -1, -1, wrap, clamp).0, [2]);
+        assert_eq!(get_pixel_modes(&image, 3, 5, wrap, clamp).0, [3]);
+        assert_eq!(
+            get_pixel_modes(&image, -1, -1, clamp, AxisBorderMode::Reflect).0,
+            [0]
+        );
+        assert_eq!(
+            get_pixel_modes(&image, 1, 2, clamp, AxisBorderMode::Reflect).0,
+            [4]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn border_mode_serde_round_trip() {
+        let mode = BorderMode::Constant([1u8, 2, 3]);
+        let json = serde_json::to_string(&mode).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BorderMode<[u8; 3]>>(&json).unwrap(),
+            mode
+        );
+
+        let json = serde_json::to_string(&AxisBorderMode::Reflect).unwrap();
+        assert_eq!(json, r#""Reflect""#);
+        assert_eq!(
+            serde_json::from_str::<AxisBorderMode>(&json).unwrap(),
+            AxisBorderMode::Reflect
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use image::GenericImageView;
+
+use crate::BorderMode;
+
+/// Returns the view of the image resolving out-of-bounds reads with the border mode,
+/// without padding, see [`Bordered`].
+#[inline]
+pub fn with_border<I: GenericImageView>(image: &I, mode: BorderMode<I::Pixel>) -> Bordered<'_, I> {
+    Bordered::new(image, mode, 0)
+}
+
+/// View of an image padded on every side by `pad` virtual pixels resolved with a border mode.
+///
+/// The view has the dimensions `(width + 2 * pad, height + 2 * pad)` and implements
+/// [`GenericImageView`], so code written for plain images can read the padding without copying.
+/// Pixel `(pad, pad)` of the view is pixel `(0, 0)` of the image.
+///
+/// Reads beyond the dimensions of the view are resolved with the border mode as well,
+/// so generic code that overshoots the right or bottom edge does not panic.
+pub struct Bordered<'a, I: GenericImageView> {
+    image: &'a I,
+    mode: BorderMode<I::Pixel>,
+    pad: u32,
+}
+
+impl<I: GenericImageView> Clone for Bordered<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: GenericImageView> Copy for Bordered<'_, I> {}
+
+impl<'a, I: GenericImageView> Bordered<'a, I> {
+    /// Creates the view of the image padded with the border mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded dimensions overflow `u32`.
+    #[inline]
+    pub fn new(image: &'a I, mode: BorderMode<I::Pixel>, pad: u32) -> Self {
+        let (width, height) = image.dimensions();
+        let padding = pad.checked_mul(2);
+        assert!(
+            padding
+                .and_then(|p| width.max(height).checked_add(p))
+                .is_some(),
+            "padded dimensions overflow"
+        );
+        Self { image, mode, pad }
+    }
+
+    /// Returns the view with the padding replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded dimensions overflow `u32`.
+    #[inline]
+    pub fn with_pad(self, pad: u32) -> Self {
+        Self::new(self.image, self.mode, pad)
+    }
+
+    /// Returns the pixel at the signed coordinates of the view, resolving the coordinates
+    /// outside of the image with the border mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+    #[inline]
+    pub fn get_pixel_signed(&self, x: i32, y: i32) -> I::Pixel {
+        self.resolve(x as i64, y as i64)
+    }
+
+    fn resolve(&self, x: i64, y: i64) -> I::Pixel {
+        let (x, y) = (x - self.pad as i64, y - self.pad as i64);
+        match self.mode.resolve(x, y, self.image.dimensions()) {
+            Ok((x, y)) => unsafe { self.image.unsafe_get_pixel(x, y) },
+            Err(pixel) => pixel,
+        }
+    }
+
+    /// Returns the padded image.
+    #[inline]
+    pub fn image(&self) -> &'a I {
+        self.image
+    }
+
+    /// Returns the border mode used for the padding.
+    #[inline]
+    pub fn mode(&self) -> BorderMode<I::Pixel> {
+        self.mode
+    }
+
+    /// Returns the number of pixels padded on every side.
+    #[inline]
+    pub fn pad(&self) -> u32 {
+        self.pad
+    }
+}
+
+impl<I: GenericImageView> GenericImageView for Bordered<'_, I> {
+    type Pixel = I::Pixel;
+
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        let (width, height) = self.image.dimensions();
+        (width + 2 * self.pad, height + 2 * self.pad)
+    }
+
+    /// Returns the pixel of the view, resolving the padding and the coordinates beyond the view
+    /// with the border mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        self.resolve(x as i64, y as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma};
+
+    use super::*;
+
+    #[test]
+    fn padded_view_resolves_border() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+
+        let clamped = Bordered::new(&image, BorderMode::Clamp, 2);
+        assert_eq!(clamped.dimensions(), (7, 6));
+        assert_eq!(clamped.get_pixel(0, 0), [0].into());
+        assert_eq!(clamped.get_pixel(2, 2), [0].into());
+        assert_eq!(clamped.get_pixel(6, 5), [5].into());
+
+        let wrapped = Bordered::new(&image, BorderMode::Wrap, 1);
+        assert_eq!(wrapped.get_pixel(0, 0), [5].into());
+
+        let constant = Bordered::new(&image, BorderMode::Constant([9].into()), 1);
+        let pixels: Vec<u8> = constant.pixels().map(|(_, _, p)| p[0]).collect();
+        #[rustfmt::skip]
+        assert_eq!(pixels, [
+            9, 9, 9, 9, 9,
+            9, 0, 1, 2, 9,
+            9, 3, 4, 5, 9,
+            9, 9, 9, 9, 9,
+        ]);
+    }
+
+    #[test]
+    fn unpadded_view_matches_image() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+        let view = Bordered::new(&image, BorderMode::Reflect, 0);
+
+        assert_eq!(view.dimensions(), image.dimensions());
+        assert!(view.pixels().eq(GenericImageView::pixels(&image)));
+    }
+
+    #[test]
+    fn border_changes_reads_of_generic_consumer() {
+        // forward difference reading one pixel past the right edge
+        fn forward_difference<I: GenericImageView<Pixel = Luma<u8>>>(image: &I) -> Vec<i16> {
+            (0..image.width())
+                .map(|x| image.get_pixel(x + 1, 0)[0] as i16 - image.get_pixel(x, 0)[0] as i16)
+                .collect()
+        }
+
+        let image = GrayImage::from_vec(3, 1, vec![10, 30, 60]).unwrap();
+
+        let reflected = with_border(&image, BorderMode::Reflect);
+        assert_eq!(forward_difference(&reflected), [20, 30, 0]);
+
+        let wrapped = with_border(&image, BorderMode::Wrap);
+        assert_eq!(forward_difference(&wrapped), [20, 30, -50]);
+    }
+
+    #[test]
+    fn signed_reads_and_padding() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+        let view = with_border(&image, BorderMode::Reflect);
+
+        assert_eq!(view.dimensions(), (3, 2));
+        assert_eq!(view.get_pixel_signed(-1, 0), [0].into());
+        assert_eq!(view.get_pixel_signed(-2, 2), [4].into());
+
+        let padded = view.with_pad(1);
+        assert_eq!(padded.dimensions(), (5, 4));
+        assert_eq!(padded.get_pixel(1, 1), [0].into());
+        assert_eq!(padded.get_pixel_signed(-1, 1), [1].into());
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::{BitOr, BitOrAssign};
+
+use image::GenericImageView;
+
+/// Set of image edges exceeded by the coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BoundsClass(u8);
+
+impl BoundsClass {
+    /// The coordinates are within the bounds of the image.
+    pub const INSIDE: Self = Self(0);
+    /// The x coordinate is left of the image.
+    pub const LEFT: Self = Self(1);
+    /// The x coordinate is right of the image.
+    pub const RIGHT: Self = Self(1 << 1);
+    /// The y coordinate is above the image.
+    pub const TOP: Self = Self(1 << 2);
+    /// The y coordinate is below the image.
+    pub const BOTTOM: Self = Self(1 << 3);
+
+    /// Returns `true` if no edge is exceeded.
+    #[inline]
+    pub const fn is_inside(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if all edges of `other` are exceeded.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for BoundsClass {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for BoundsClass {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Returns which edges of the image the given coordinates exceed.
+pub fn classify_bounds<I: GenericImageView>(image: &I, x: i32, y: i32) -> BoundsClass {
+    let (width, height) = image.dimensions();
+    let mut class = BoundsClass::INSIDE;
+
+    if x < 0 {
+        class |= BoundsClass::LEFT;
+    } else if x as u32 >= width {
+        class |= BoundsClass::RIGHT;
+    }
+
+    if y < 0 {
+        class |= BoundsClass::TOP;
+    } else if y as u32 >= height {
+        class |= BoundsClass::BOTTOM;
+    }
+
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GrayImage;
+
+    use super::*;
+
+    #[test]
+    fn classify_bounds_reports_edges() {
+        let image = GrayImage::new(3, 2);
+
+        assert!(classify_bounds(&image, 2, 1).is_inside());
+        assert_eq!(classify_bounds(&image, -1, 0), BoundsClass::LEFT);
+        assert_eq!(classify_bounds(&image, 0, 2), BoundsClass::BOTTOM);
+
+        let class = classify_bounds(&image, 3, -1);
+        assert!(!class.is_inside());
+        assert!(class.contains(BoundsClass::RIGHT | BoundsClass::TOP));
+        assert!(!class.contains(BoundsClass::LEFT));
+        assert!(!class.contains(BoundsClass::BOTTOM));
+    }
+}
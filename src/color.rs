@@ -0,0 +1,308 @@
+use image::{
+    GenericImage, GenericImageView, GrayImage, ImageBuffer, Pixel, Primitive, Rgb, Rgba, RgbaImage,
+};
+
+use crate::math::{bilinear_taps, from_f32, to_f32};
+use crate::{clamp_pixel, get_pixel, PixelBuffer};
+
+/// Rec. 709 luma coefficients for the red, green and blue channels.
+const REC709_LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Returns the Rec. 709 luma of the pixel at the given coordinates,
+/// or `None` if the coordinates are out of bounds.
+///
+/// The pixel is converted to RGB first: grayscale pixels contribute their gray value to every
+/// channel and alpha is ignored. The luma is in the subpixel range, e.g. `[0, 255]` for `u8`.
+pub fn luminance_at<I: GenericImageView>(image: &I, x: i32, y: i32) -> Option<f32> {
+    get_pixel(image, x, y).map(luminance)
+}
+
+/// Returns the Rec. 709 luma of the pixel in the subpixel range.
+#[inline]
+pub(crate) fn luminance<P: Pixel>(pixel: P) -> f32 {
+    let rgb = pixel.to_rgb();
+    rgb.0
+        .iter()
+        .zip(REC709_LUMA)
+        .map(|(&c, weight)| to_f32(c) * weight)
+        .sum()
+}
+
+/// Returns the luma of the image quantized to `levels` evenly spaced gray values
+/// with Floyd–Steinberg error diffusion.
+///
+/// The luma is computed as in [`luminance_at`] and scaled from the subpixel range to `[0, 255]`.
+/// The quantization error is only diffused to the neighbours within the image bounds.
+///
+/// # Panics
+///
+/// Panics if `levels` is less than `2`.
+pub fn dither_to_luma<I: GenericImageView>(image: &I, levels: u8) -> GrayImage {
+    assert!(levels >= 2, "levels must be at least 2");
+
+    let (width, height) = image.dimensions();
+    let scale = 255.0 / to_f32(<I::Pixel as Pixel>::Subpixel::DEFAULT_MAX_VALUE);
+    let step = 255.0 / (levels - 1) as f32;
+
+    let mut luma: Vec<f32> = image
+        .pixels()
+        .map(|(_, _, pixel)| luminance(pixel) * scale)
+        .collect();
+
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+    let mut output = GrayImage::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let old = luma[index(x, y)];
+            let new = ((old / step).round() * step).clamp(0.0, 255.0);
+            let error = old - new;
+            output.put_pixel(x, y, [new.round() as u8].into());
+
+            let right = x + 1 < width;
+            let below = y + 1 < height;
+            if right {
+                luma[index(x + 1, y)] += error * 7.0 / 16.0;
+            }
+            if below && x > 0 {
+                luma[index(x - 1, y + 1)] += error * 3.0 / 16.0;
+            }
+            if below {
+                luma[index(x, y + 1)] += error * 5.0 / 16.0;
+            }
+            if below && right {
+                luma[index(x + 1, y + 1)] += error / 16.0;
+            }
+        }
+    }
+    output
+}
+
+/// Converts the sRGB encoded value in `[0, 1]` to linear light.
+#[inline]
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts the linear light value in `[0, 1]` to sRGB encoding.
+#[inline]
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Returns the bilinearly interpolated color at the given subpixel coordinates,
+/// blending the four clamped taps in linear light.
+///
+/// Each tap is converted from sRGB to linear light, blended and converted back, so the
+/// result is sRGB encoded with channels in `[0, 1]`. Alpha is ignored and grayscale pixels
+/// are treated as gray RGB colors. Returns `None` for an empty image or non-finite coordinates.
+pub fn sample_bilinear_srgb<I, P>(image: &I, x: f32, y: f32) -> Option<Rgb<f32>>
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel<Subpixel = u8>,
+{
+    if image.width() == 0 || image.height() == 0 {
+        return None;
+    }
+
+    let (coords, weights) = bilinear_taps(x, y)?;
+    let mut linear = [0.0f32; 3];
+    for ((x, y), weight) in coords.into_iter().zip(weights) {
+        let rgb = clamp_pixel(image, x, y).to_rgb();
+        for (sum, c) in linear.iter_mut().zip(rgb.0) {
+            *sum += srgb_to_linear(c as f32 / 255.0) * weight;
+        }
+    }
+
+    Some(Rgb(linear.map(linear_to_srgb)))
+}
+
+/// Multiplies the color channels of the pixel at the given coordinates by its alpha.
+///
+/// Returns `false` if the coordinates are out of bounds.
+pub fn premultiply_at<I: GenericImage<Pixel = Rgba<u8>>>(image: &mut I, x: i32, y: i32) -> bool {
+    let Some(Rgba([r, g, b, a])) = get_pixel(image, x, y) else {
+        return false;
+    };
+
+    let mul = |c: u8| ((c as u32 * a as u32 + 127) / 255) as u8;
+    unsafe { image.unsafe_put_pixel(x as u32, y as u32, Rgba([mul(r), mul(g), mul(b), a])) };
+    true
+}
+
+/// Divides the color channels of the pixel at the given coordinates by its alpha,
+/// reverting [`premultiply_at`]. Color channels of fully transparent pixels are set to zero.
+///
+/// Returns `false` if the coordinates are out of bounds.
+pub fn unpremultiply_at<I: GenericImage<Pixel = Rgba<u8>>>(image: &mut I, x: i32, y: i32) -> bool {
+    let Some(Rgba([r, g, b, a])) = get_pixel(image, x, y) else {
+        return false;
+    };
+
+    let div = |c: u8| match a {
+        0 => 0,
+        a => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+    };
+    unsafe { image.unsafe_put_pixel(x as u32, y as u32, Rgba([div(r), div(g), div(b), a])) };
+    true
+}
+
+/// Returns the image with the color channels multiplied by alpha, normalized to `[0, 1]`.
+///
+/// Premultiplied colors can be interpolated without halos around transparent pixels.
+pub fn to_premultiplied<I: GenericImageView<Pixel = Rgba<u8>>>(
+    image: &I,
+) -> PixelBuffer<Rgba<f32>> {
+    ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let Rgba([r, g, b, a]) = unsafe { image.unsafe_get_pixel(x, y) };
+        let a = a as f32 / 255.0;
+        let mul = |c: u8| c as f32 / 255.0 * a;
+        Rgba([mul(r), mul(g), mul(b), a])
+    })
+}
+
+/// Returns the image with the normalized premultiplied color channels divided by alpha,
+/// reverting [`to_premultiplied`].
+///
+/// Channels are rounded and saturated to `[0, 255]`. Color channels of fully transparent
+/// pixels are set to zero.
+pub fn from_premultiplied<I: GenericImageView<Pixel = Rgba<f32>>>(image: &I) -> RgbaImage {
+    ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let Rgba([r, g, b, a]) = unsafe { image.unsafe_get_pixel(x, y) };
+        let div = |c: f32| if a > 0.0 { from_f32(c / a * 255.0) } else { 0 };
+        Rgba([div(r), div(g), div(b), from_f32(a * 255.0)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayAlphaImage, RgbImage, RgbaImage};
+
+    use super::*;
+
+    #[test]
+    fn luminance_of_rgb_pixel() {
+        let image = RgbImage::from_pixel(1, 1, [200, 100, 50].into());
+        let luma = luminance_at(&image, 0, 0).unwrap();
+
+        assert!((luma - (0.2126 * 200.0 + 0.7152 * 100.0 + 0.0722 * 50.0)).abs() < 1e-3);
+        assert_eq!(luminance_at(&image, 1, 0), None);
+    }
+
+    #[test]
+    fn luminance_of_gray_alpha_pixel() {
+        let image = GrayAlphaImage::from_pixel(1, 1, [80, 10].into());
+        let luma = luminance_at(&image, 0, 0).unwrap();
+
+        assert!((luma - 80.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        let mut image = RgbaImage::from_vec(
+            3,
+            1,
+            vec![10, 128, 255, 255, 200, 100, 50, 0, 200, 100, 50, 128],
+        )
+        .unwrap();
+
+        assert!(premultiply_at(&mut image, 0, 0));
+        assert_eq!(image.get_pixel(0, 0).0, [10, 128, 255, 255]);
+        assert!(unpremultiply_at(&mut image, 0, 0));
+        assert_eq!(image.get_pixel(0, 0).0, [10, 128, 255, 255]);
+
+        assert!(premultiply_at(&mut image, 1, 0));
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+        assert!(unpremultiply_at(&mut image, 1, 0));
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+
+        assert!(premultiply_at(&mut image, 2, 0));
+        assert_eq!(image.get_pixel(2, 0).0, [100, 50, 25, 128]);
+        assert!(unpremultiply_at(&mut image, 2, 0));
+        assert_eq!(image.get_pixel(2, 0).0, [199, 100, 50, 128]);
+
+        assert!(!premultiply_at(&mut image, 3, 0));
+        assert!(!unpremultiply_at(&mut image, 0, -1));
+    }
+
+    #[test]
+    fn premultiplied_image_round_trip() {
+        let image = RgbaImage::from_vec(
+            3,
+            1,
+            vec![10, 128, 255, 255, 200, 100, 50, 0, 200, 100, 50, 128],
+        )
+        .unwrap();
+
+        let premultiplied = to_premultiplied(&image);
+        let Rgba([r, g, b, a]) = *premultiplied.get_pixel(2, 0);
+        assert!((a - 128.0 / 255.0).abs() < 1e-6);
+        assert!((r - 200.0 / 255.0 * a).abs() < 1e-6);
+        assert!((g - 100.0 / 255.0 * a).abs() < 1e-6);
+        assert!((b - 50.0 / 255.0 * a).abs() < 1e-6);
+        assert_eq!(premultiplied.get_pixel(1, 0).0, [0.0; 4]);
+
+        let restored = from_premultiplied(&premultiplied);
+        assert_eq!(restored.get_pixel(0, 0), image.get_pixel(0, 0));
+        assert_eq!(restored.get_pixel(1, 0).0, [0, 0, 0, 0]);
+        assert_eq!(restored.get_pixel(2, 0), image.get_pixel(2, 0));
+    }
+
+    #[test]
+    fn srgb_bilinear_blends_in_linear_light() {
+        let image = RgbImage::from_vec(2, 1, vec![0, 0, 0, 255, 255, 255]).unwrap();
+
+        let Rgb([r, g, b]) = sample_bilinear_srgb(&image, 0.5, 0.0).unwrap();
+        assert_eq!((r, g), (b, b));
+        assert!((r - 0.7354).abs() < 1e-3);
+
+        // naive blending of the encoded values gives the midpoint instead
+        let naive = crate::get_pixel_bilinear(&image, 0.5, 0.0).unwrap();
+        assert!((naive.0[0] as f32 / 255.0 - r).abs() > 0.2);
+
+        let Rgb([r, ..]) = sample_bilinear_srgb(&image, 1.0, 0.0).unwrap();
+        assert!((r - 1.0).abs() < 1e-5);
+        assert!(sample_bilinear_srgb(&RgbImage::new(0, 0), 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn dither_to_two_levels_is_binary() {
+        let image = RgbImage::from_fn(8, 8, |x, y| [(x * 32) as u8, (y * 32) as u8, 128].into());
+        let dithered = dither_to_luma(&image, 2);
+
+        assert_eq!(dithered.dimensions(), (8, 8));
+        assert!(dithered.pixels().all(|p| p[0] == 0 || p[0] == 255));
+    }
+
+    #[test]
+    fn dither_preserves_average_luma() {
+        let image = image::GrayImage::from_pixel(16, 16, [128].into());
+        let dithered = dither_to_luma(&image, 2);
+
+        let white = dithered.pixels().filter(|p| p[0] == 255).count();
+        assert!((120..=136).contains(&white), "{white} white pixels");
+
+        let three = dither_to_luma(&image, 3);
+        assert!(three.pixels().all(|p| p[0] == 128));
+    }
+
+    #[test]
+    #[should_panic(expected = "levels must be at least 2")]
+    fn dither_to_one_level() {
+        dither_to_luma(&RgbImage::new(1, 1), 1);
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        for value in [0.0, 0.002, 0.04, 0.2, 0.5, 0.9, 1.0] {
+            assert!((linear_to_srgb(srgb_to_linear(value)) - value).abs() < 1e-5);
+        }
+    }
+}
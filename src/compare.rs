@@ -0,0 +1,182 @@
+use image::{GenericImageView, Pixel};
+
+use crate::math::to_f64;
+use crate::{get_pixel, Rect};
+
+/// Returns the per-channel absolute difference of the pixels of both images at the given coordinates,
+/// or `None` if the coordinates are out of bounds of either image.
+pub fn abs_diff_at<A, B>(a: &A, b: &B, x: i32, y: i32) -> Option<A::Pixel>
+where
+    A: GenericImageView,
+    B: GenericImageView<Pixel = A::Pixel>,
+{
+    let (pa, pb) = (get_pixel(a, x, y)?, get_pixel(b, x, y)?);
+    Some(pa.map2(&pb, |a, b| if a > b { a - b } else { b - a }))
+}
+
+/// Returns the sum of squared channel differences between `b` placed with its top-left corner
+/// at `a_origin` on `a` and the pixels of `a` under it.
+///
+/// Only the overlap of both images is compared, the parts of `b` outside of `a` are skipped,
+/// so the sum is `0` if they do not overlap. The sum is accumulated in `f64` and rounded to
+/// the nearest integer once, saturating at `u64::MAX`, so the differences of float subpixels
+/// contribute as well.
+pub fn ssd<A, B>(a: &A, b: &B, a_origin: (i32, i32)) -> u64
+where
+    A: GenericImageView,
+    B: GenericImageView<Pixel = A::Pixel>,
+{
+    let mut sum = 0.0;
+    for_each_overlap(a, b, a_origin, |pa, pb| {
+        for (&ca, &cb) in pa.channels().iter().zip(pb.channels()) {
+            let d = to_f64(ca) - to_f64(cb);
+            sum += d * d;
+        }
+    });
+    sum.round() as u64
+}
+
+/// Returns the normalized cross-correlation in `[-1, 1]` between `b` placed with its top-left
+/// corner at `a_origin` on `a` and the pixels of `a` under it.
+///
+/// The channels of the overlap are pooled and their means subtracted, so the score does not depend
+/// on the brightness and contrast of either image. Returns `0.0` if the images do not overlap
+/// or either overlap is uniform.
+pub fn ncc<A, B>(a: &A, b: &B, a_origin: (i32, i32)) -> f64
+where
+    A: GenericImageView,
+    B: GenericImageView<Pixel = A::Pixel>,
+{
+    let (mut sum_a, mut sum_b, mut count) = (0.0, 0.0, 0u64);
+    for_each_overlap(a, b, a_origin, |pa, pb| {
+        for (&ca, &cb) in pa.channels().iter().zip(pb.channels()) {
+            sum_a += to_f64(ca);
+            sum_b += to_f64(cb);
+            count += 1;
+        }
+    });
+    if count == 0 {
+        return 0.0;
+    }
+
+    let (mean_a, mean_b) = (sum_a / count as f64, sum_b / count as f64);
+    let (mut cross, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for_each_overlap(a, b, a_origin, |pa, pb| {
+        for (&ca, &cb) in pa.channels().iter().zip(pb.channels()) {
+            let (da, db) = (to_f64(ca) - mean_a, to_f64(cb) - mean_b);
+            cross += da * db;
+            var_a += da * da;
+            var_b += db * db;
+        }
+    });
+
+    let norm = (var_a * var_b).sqrt();
+    if norm > 0.0 {
+        (cross / norm).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Calls `f` with the pixels of `a` and `b` at every position of their overlap
+/// when `b` is placed at `a_origin` on `a`.
+fn for_each_overlap<A, B, F>(a: &A, b: &B, a_origin: (i32, i32), mut f: F)
+where
+    A: GenericImageView,
+    B: GenericImageView<Pixel = A::Pixel>,
+    F: FnMut(A::Pixel, A::Pixel),
+{
+    let (width, height) = a.dimensions();
+    let (ox, oy) = a_origin;
+    let Some(rect) = Rect::new(ox, oy, b.width(), b.height()).clip(width, height) else {
+        return;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        let by = (y as i64 - oy as i64) as u32;
+        for x in x0..x0 + rect.width {
+            let bx = (x as i64 - ox as i64) as u32;
+            f(unsafe { a.unsafe_get_pixel(x, y) }, unsafe {
+                b.unsafe_get_pixel(bx, by)
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Rgb32FImage, RgbImage};
+
+    use super::*;
+
+    #[test]
+    fn ssd_over_overlap() {
+        let a = GrayImage::from_fn(4, 3, |x, y| [(y * 4 + x) as u8 * 10].into());
+
+        assert_eq!(ssd(&a, &a, (0, 0)), 0);
+
+        let b = GrayImage::from_vec(2, 2, vec![50, 60, 90, 100]).unwrap();
+        assert_eq!(ssd(&a, &b, (1, 1)), 0);
+        assert_eq!(ssd(&a, &b, (0, 1)), 4 * 100);
+        assert_eq!(ssd(&a, &b, (3, 2)), 60 * 60);
+        assert_eq!(ssd(&a, &b, (-1, -1)), 100 * 100);
+        assert_eq!(ssd(&a, &b, (4, 0)), 0);
+
+        let a = RgbImage::from_pixel(1, 1, [1, 2, 3].into());
+        let b = RgbImage::from_pixel(1, 1, [3, 2, 0].into());
+        assert_eq!(ssd(&a, &b, (0, 0)), 4 + 9);
+    }
+
+    #[test]
+    fn ssd_of_float_images() {
+        let a = Rgb32FImage::from_pixel(2, 2, [0.05, 0.1, 0.0].into());
+        let b = Rgb32FImage::from_pixel(2, 2, [0.95, 1.0, 0.9].into());
+
+        // 12 channels differing by 0.9 each
+        assert_eq!(ssd(&a, &b, (0, 0)), 10);
+        assert_eq!(ssd(&a, &a, (0, 0)), 0);
+    }
+
+    #[test]
+    fn ncc_of_image_with_itself() {
+        let a = RgbImage::from_fn(5, 4, |x, y| [(x * 40) as u8, (y * 60) as u8, 7].into());
+
+        assert!((ncc(&a, &a, (0, 0)) - 1.0).abs() < 1e-12);
+
+        let brighter = RgbImage::from_fn(5, 4, |x, y| a.get_pixel(x, y).map(|c| c / 2 + 100));
+        assert!((ncc(&a, &brighter, (0, 0)) - 1.0).abs() < 1e-3);
+
+        let inverted = RgbImage::from_fn(5, 4, |x, y| a.get_pixel(x, y).map(|c| 255 - c));
+        assert!((ncc(&a, &inverted, (0, 0)) + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ncc_without_overlap_or_variance() {
+        let a = GrayImage::from_fn(3, 3, |x, y| [(x + y) as u8].into());
+        let flat = GrayImage::from_pixel(2, 2, [9].into());
+
+        assert_eq!(ncc(&a, &flat, (0, 0)), 0.0);
+        assert_eq!(ncc(&a, &a, (3, 0)), 0.0);
+        assert_eq!(ncc(&a, &a, (-3, -3)), 0.0);
+    }
+
+    #[test]
+    fn abs_diff_of_two_images() {
+        let a = GrayImage::from_vec(2, 2, vec![10, 200, 0, 255]).unwrap();
+        let b = GrayImage::from_vec(2, 2, vec![30, 100, 0, 0]).unwrap();
+
+        assert_eq!(abs_diff_at(&a, &b, 0, 0), Some([20].into()));
+        assert_eq!(abs_diff_at(&a, &b, 1, 0), Some([100].into()));
+        assert_eq!(abs_diff_at(&a, &b, 0, 1), Some([0].into()));
+        assert_eq!(abs_diff_at(&a, &b, 1, 1), Some([255].into()));
+        assert_eq!(abs_diff_at(&a, &b, 2, 0), None);
+
+        let small = GrayImage::new(1, 1);
+        assert_eq!(abs_diff_at(&a, &small, 1, 1), None);
+
+        let a = RgbImage::from_pixel(1, 1, [1, 2, 3].into());
+        let b = RgbImage::from_pixel(1, 1, [3, 2, 1].into());
+        assert_eq!(abs_diff_at(&a, &b, 0, 0), Some([2, 0, 2].into()));
+    }
+}
@@ -0,0 +1,356 @@
+use image::GenericImageView;
+
+use crate::{get_pixel, in_bounds};
+
+/// Returns the coordinates of the pixel at the given row-major offset,
+/// or `None` if the offset is out of bounds.
+#[inline]
+pub fn offset_to_coordinate<I: GenericImageView>(image: &I, offset: usize) -> Option<(u32, u32)> {
+    let (width, height) = image.dimensions();
+    let offset = offset as u64;
+    (offset < width as u64 * height as u64).then(|| {
+        (
+            (offset % width as u64) as u32,
+            (offset / width as u64) as u32,
+        )
+    })
+}
+
+/// Returns the row-major offset of the pixel at the given coordinates,
+/// or `None` if the coordinates are out of bounds.
+#[inline]
+pub fn coordinate_to_offset<I: GenericImageView>(image: &I, x: i32, y: i32) -> Option<usize> {
+    in_bounds(image, x, y)
+        .then(|| y as u64 * image.width() as u64 + x as u64)
+        .and_then(|offset| usize::try_from(offset).ok())
+}
+
+/// Returns the coordinates offset by the deltas if they are within the bounds of the image,
+/// or `None` if they are out of bounds or the offset overflows.
+#[inline]
+pub fn offset_coordinate<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+    dx: i64,
+    dy: i64,
+) -> Option<(u32, u32)> {
+    let (width, height) = image.dimensions();
+    let x = u32::try_from((x as i64).checked_add(dx)?).ok()?;
+    let y = u32::try_from((y as i64).checked_add(dy)?).ok()?;
+    (x < width && y < height).then_some((x, y))
+}
+
+/// Returns the pixel at the base coordinates offset by the deltas,
+/// or `None` if the offset coordinates are out of bounds.
+///
+/// The offset is computed in `i64`, so it never overflows.
+#[inline]
+pub fn get_pixel_offset<I: GenericImageView>(
+    image: &I,
+    base: (u32, u32),
+    dx: i32,
+    dy: i32,
+) -> Option<I::Pixel> {
+    let (width, height) = image.dimensions();
+    let x = base.0 as i64 + dx as i64;
+    let y = base.1 as i64 + dy as i64;
+    ((0..width as i64).contains(&x) && (0..height as i64).contains(&y))
+        .then(|| unsafe { image.unsafe_get_pixel(x as u32, y as u32) })
+}
+
+/// Returns the Manhattan (L1) distance between the coordinates.
+#[inline]
+pub fn manhattan_distance(a: (u32, u32), b: (u32, u32)) -> u64 {
+    a.0.abs_diff(b.0) as u64 + a.1.abs_diff(b.1) as u64
+}
+
+/// Returns the Chebyshev (L∞) distance between the coordinates.
+#[inline]
+pub fn chebyshev_distance(a: (u32, u32), b: (u32, u32)) -> u64 {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1)) as u64
+}
+
+/// Describes how continuous coordinates are snapped to pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapPolicy {
+    /// Rounds the value towards negative infinity.
+    Floor,
+    /// Rounds the value to the nearest integer, with halves away from zero.
+    Round,
+    /// Rounds the value towards positive infinity.
+    Ceil,
+}
+
+impl SnapPolicy {
+    /// Returns the snapped value, or `None` if it is not finite or does not fit in `i32`.
+    #[inline]
+    pub fn snap(self, value: f32) -> Option<i32> {
+        let value = match self {
+            SnapPolicy::Floor => value.floor(),
+            SnapPolicy::Round => value.round(),
+            SnapPolicy::Ceil => value.ceil(),
+        };
+        (value.is_finite() && value >= i32::MIN as f32 && value < i32::MAX as f32)
+            .then_some(value as i32)
+    }
+}
+
+/// Returns the pixel at the continuous coordinates snapped with the policy,
+/// or `None` if the snapped coordinates are out of bounds.
+#[inline]
+pub fn get_pixel_snapped<I: GenericImageView>(
+    image: &I,
+    x: f32,
+    y: f32,
+    policy: SnapPolicy,
+) -> Option<I::Pixel> {
+    get_pixel(image, policy.snap(x)?, policy.snap(y)?)
+}
+
+/// Iterator over all coordinates of an image in row-major order.
+struct Coords {
+    width: u32,
+    next: u64,
+    end: u64,
+}
+
+impl Iterator for Coords {
+    type Item = (u32, u32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+
+        let offset = self.next;
+        self.next += 1;
+        Some((
+            (offset % self.width as u64) as u32,
+            (offset / self.width as u64) as u32,
+        ))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.end - self.next).unwrap_or(usize::MAX);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Coords {}
+
+/// Returns an iterator over all coordinates of the image in row-major order, without the pixels.
+#[inline]
+pub fn coords<I: GenericImageView>(image: &I) -> impl ExactSizeIterator<Item = (u32, u32)> {
+    let (width, height) = image.dimensions();
+    Coords {
+        width,
+        next: 0,
+        end: width as u64 * height as u64,
+    }
+}
+
+/// Returns an iterator over the top-left coordinates of the `win_w x win_h` windows
+/// placed with the given strides, in row-major order.
+///
+/// Only windows fully within the image are yielded, partial windows at the right
+/// and bottom edges are skipped.
+///
+/// # Panics
+///
+/// Panics if the window dimensions or the strides are zero.
+pub fn windows<I: GenericImageView>(
+    image: &I,
+    win_w: u32,
+    win_h: u32,
+    stride_x: u32,
+    stride_y: u32,
+) -> impl Iterator<Item = (u32, u32)> {
+    assert!(win_w > 0 && win_h > 0, "window dimensions must be non-zero");
+    assert!(
+        stride_x > 0 && stride_y > 0,
+        "window strides must be non-zero"
+    );
+
+    let (width, height) = image.dimensions();
+    let xs = width
+        .checked_sub(win_w)
+        .map_or(0, |last| last / stride_x + 1);
+    let ys = height
+        .checked_sub(win_h)
+        .map_or(0, |last| last / stride_y + 1);
+    (0..ys).flat_map(move |j| (0..xs).map(move |i| (i * stride_x, j * stride_y)))
+}
+
+/// Returns an iterator over the given coordinates that lie within the bounds of the image.
+#[inline]
+pub fn valid_coords<'a, I, It>(image: &'a I, coords: It) -> impl Iterator<Item = (u32, u32)> + 'a
+where
+    I: GenericImageView,
+    It: IntoIterator<Item = (i32, i32)>,
+    It::IntoIter: 'a,
+{
+    coords
+        .into_iter()
+        .filter(|&(x, y)| in_bounds(image, x, y))
+        .map(|(x, y)| (x as u32, y as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GrayImage;
+
+    use super::*;
+
+    #[test]
+    fn grid_distances() {
+        assert_eq!(manhattan_distance((1, 2), (1, 2)), 0);
+        assert_eq!(manhattan_distance((1, 2), (4, 0)), 5);
+        assert_eq!(chebyshev_distance((1, 2), (4, 0)), 3);
+        assert_eq!(chebyshev_distance((4, 0), (1, 2)), 3);
+
+        let far = ((0, u32::MAX), (u32::MAX, 0));
+        assert_eq!(manhattan_distance(far.0, far.1), 2 * u32::MAX as u64);
+        assert_eq!(chebyshev_distance(far.0, far.1), u32::MAX as u64);
+    }
+
+    #[test]
+    fn pixel_offset_does_not_overflow() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+
+        assert_eq!(get_pixel_offset(&image, (1, 1), 1, -1), Some([2].into()));
+        assert_eq!(get_pixel_offset(&image, (2, 0), -2, 0), Some([0].into()));
+        assert_eq!(get_pixel_offset(&image, (1, 1), i32::MIN, 0), None);
+        assert_eq!(get_pixel_offset(&image, (1, 1), 0, i32::MAX), None);
+        assert_eq!(get_pixel_offset(&image, (u32::MAX, 0), i32::MIN, 0), None);
+        assert_eq!(get_pixel_offset(&image, (3, 0), -1, 0), Some([2].into()));
+    }
+
+    #[test]
+    fn snapped_pixel_per_policy() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+
+        assert_eq!(
+            get_pixel_snapped(&image, 1.6, 0.2, SnapPolicy::Floor),
+            Some([1].into())
+        );
+        assert_eq!(
+            get_pixel_snapped(&image, 1.6, 0.2, SnapPolicy::Round),
+            Some([2].into())
+        );
+        assert_eq!(
+            get_pixel_snapped(&image, 1.6, 0.2, SnapPolicy::Ceil),
+            Some([5].into())
+        );
+
+        let small = GrayImage::new(2, 2);
+        assert_eq!(get_pixel_snapped(&small, 1.6, 0.2, SnapPolicy::Ceil), None);
+        assert_eq!(
+            get_pixel_snapped(&small, -0.2, 0.0, SnapPolicy::Floor),
+            None
+        );
+        assert_eq!(
+            get_pixel_snapped(&small, -0.2, 0.0, SnapPolicy::Round),
+            Some([0].into())
+        );
+        assert_eq!(SnapPolicy::Floor.snap(f32::NAN), None);
+        assert_eq!(SnapPolicy::Ceil.snap(3e9), None);
+    }
+
+    #[test]
+    fn every_coordinate_round_trips_through_flat_buffer() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(10 * y + x) as u8].into());
+
+        for (x, y) in coords(&image) {
+            let offset = coordinate_to_offset(&image, x as i32, y as i32).unwrap();
+            assert_eq!(image.as_raw()[offset], image.get_pixel(x, y)[0]);
+            assert_eq!(offset_to_coordinate(&image, offset), Some((x, y)));
+        }
+    }
+
+    #[test]
+    fn offset_and_coordinate_round_trip() {
+        let image = GrayImage::new(3, 4);
+
+        for offset in 0..12 {
+            let (x, y) = offset_to_coordinate(&image, offset).unwrap();
+            assert_eq!((x, y), (offset as u32 % 3, offset as u32 / 3));
+            assert_eq!(
+                coordinate_to_offset(&image, x as i32, y as i32),
+                Some(offset)
+            );
+        }
+
+        assert_eq!(offset_to_coordinate(&image, 12), None);
+        assert_eq!(coordinate_to_offset(&image, 3, 0), None);
+        assert_eq!(coordinate_to_offset(&image, 0, -1), None);
+        assert_eq!(offset_to_coordinate(&GrayImage::new(0, 0), 0), None);
+    }
+
+    #[test]
+    fn coords_in_scan_order() {
+        let image = GrayImage::new(3, 2);
+        let mut it = coords(&image);
+
+        assert_eq!(it.len(), 6);
+        assert_eq!(it.next(), Some((0, 0)));
+        assert_eq!(it.size_hint(), (5, Some(5)));
+        assert_eq!(
+            it.collect::<Vec<_>>(),
+            [(1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+
+        assert_eq!(coords(&GrayImage::new(0, 4)).len(), 0);
+        assert_eq!(coords(&GrayImage::new(4, 0)).next(), None);
+    }
+
+    #[test]
+    fn offset_coordinate_checks_overflow_and_bounds() {
+        let image = GrayImage::new(4, 3);
+
+        assert_eq!(offset_coordinate(&image, 1, 1, 2, 1), Some((3, 2)));
+        assert_eq!(offset_coordinate(&image, -1, 5, 1, -5), Some((0, 0)));
+        assert_eq!(offset_coordinate(&image, 1, 1, 3, 0), None);
+        assert_eq!(offset_coordinate(&image, 1, 1, 0, -2), None);
+        assert_eq!(offset_coordinate(&image, 1, 1, i64::MAX, 0), None);
+        assert_eq!(offset_coordinate(&image, -1, 0, i64::MIN, 0), None);
+    }
+
+    #[test]
+    fn windows_with_stride() {
+        let image = GrayImage::new(5, 5);
+
+        let all: Vec<_> = windows(&image, 3, 3, 2, 2).collect();
+        assert_eq!(all, [(0, 0), (2, 0), (0, 2), (2, 2)]);
+
+        assert_eq!(windows(&image, 3, 3, 1, 1).count(), 9);
+        assert_eq!(windows(&image, 2, 5, 2, 1).count(), 2);
+        assert_eq!(windows(&image, 6, 1, 1, 1).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn windows_with_zero_stride() {
+        let _ = windows(&GrayImage::new(5, 5), 3, 3, 0, 1);
+    }
+
+    #[test]
+    fn valid_coords_drops_out_of_bounds() {
+        let image = GrayImage::new(3, 2);
+        let coords = vec![
+            (0, 0),
+            (-1, 0),
+            (2, 1),
+            (3, 1),
+            (1, -5),
+            (1, 1),
+            (i32::MAX, 0),
+        ];
+
+        let valid: Vec<_> = valid_coords(&image, coords).collect();
+        assert_eq!(valid, [(0, 0), (2, 1), (1, 1)]);
+    }
+}
@@ -0,0 +1,257 @@
+use image::{GenericImage, GenericImageView};
+
+use crate::{get_pixel, set_pixel, Rect};
+
+/// Sets every pixel of the rectangle clipped to the image to `on` where the predicate
+/// holds for its coordinates and to `off` elsewhere.
+pub fn apply_mask<I, F>(image: &mut I, rect: Rect, on: I::Pixel, off: I::Pixel, pred: F)
+where
+    I: GenericImage,
+    F: Fn(u32, u32) -> bool,
+{
+    let (width, height) = image.dimensions();
+    let Some(rect) = rect.clip(width, height) else {
+        return;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        for x in x0..x0 + rect.width {
+            let pixel = if pred(x, y) { on } else { off };
+            unsafe { image.unsafe_put_pixel(x, y, pixel) };
+        }
+    }
+}
+
+/// Sets every pixel of the rectangle clipped to the image to `color`
+/// and returns the number of pixels filled.
+pub fn fill_rect<I: GenericImage>(image: &mut I, rect: Rect, color: I::Pixel) -> u64 {
+    let (width, height) = image.dimensions();
+    let Some(rect) = rect.clip(width, height) else {
+        return 0;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        for x in x0..x0 + rect.width {
+            unsafe { image.unsafe_put_pixel(x, y, color) };
+        }
+    }
+    rect.width as u64 * rect.height as u64
+}
+
+/// Draws the 1-pixel-wide outline of the rectangle, skipping the pixels outside of the image.
+pub fn draw_rect_border<I: GenericImage>(image: &mut I, rect: Rect, color: I::Pixel) {
+    let (width, height) = image.dimensions();
+    let Some(clipped) = rect.clip(width, height) else {
+        return;
+    };
+
+    let (left, top) = (rect.x, rect.y);
+    let right = (rect.x as i64 + rect.width as i64 - 1).min(i32::MAX as i64) as i32;
+    let bottom = (rect.y as i64 + rect.height as i64 - 1).min(i32::MAX as i64) as i32;
+    let (x0, y0) = (clipped.x, clipped.y);
+    let (x1, y1) = (x0 + clipped.width as i32, y0 + clipped.height as i32);
+
+    for x in x0..x1 {
+        set_pixel(image, x, top, color);
+        set_pixel(image, x, bottom, color);
+    }
+    for y in y0..y1 {
+        set_pixel(image, left, y, color);
+        set_pixel(image, right, y, color);
+    }
+}
+
+/// Copies the source image into the image with its top-left corner at the given coordinates,
+/// clipping the part of the source outside of the image.
+///
+/// Returns `true` if any pixels were copied.
+pub fn blit_from<I, S>(image: &mut I, src: &S, dst_x: i32, dst_y: i32) -> bool
+where
+    I: GenericImage,
+    S: GenericImageView<Pixel = I::Pixel>,
+{
+    let (width, height) = image.dimensions();
+    let rect = Rect::new(dst_x, dst_y, src.width(), src.height());
+    let Some(rect) = rect.clip(width, height) else {
+        return false;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        let src_y = (y as i64 - dst_y as i64) as u32;
+        for x in x0..x0 + rect.width {
+            let src_x = (x as i64 - dst_x as i64) as u32;
+            let pixel = unsafe { src.unsafe_get_pixel(src_x, src_y) };
+            unsafe { image.unsafe_put_pixel(x, y, pixel) };
+        }
+    }
+    true
+}
+
+/// Fills the 4-connected region of pixels equal to the pixel at the given coordinates
+/// and returns the bounding rectangle of the filled region.
+///
+/// Returns `None` if the coordinates are out of bounds.
+pub fn flood_fill<I>(image: &mut I, x: i32, y: i32, fill: I::Pixel) -> Option<Rect>
+where
+    I: GenericImage,
+    I::Pixel: PartialEq,
+{
+    let target = get_pixel(image, x, y)?;
+    let (width, height) = image.dimensions();
+    let mut visited = vec![false; width as usize * height as usize];
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+
+    let (x, y) = (x as u32, y as u32);
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (x, y, x, y);
+    let mut stack = vec![(x, y)];
+    visited[index(x, y)] = true;
+
+    while let Some((x, y)) = stack.pop() {
+        unsafe { image.unsafe_put_pixel(x, y, fill) };
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+
+        let neighbors = [
+            x.checked_sub(1).map(|x| (x, y)),
+            (x + 1 < width).then(|| (x + 1, y)),
+            y.checked_sub(1).map(|y| (x, y)),
+            (y + 1 < height).then(|| (x, y + 1)),
+        ];
+        for (nx, ny) in neighbors.into_iter().flatten() {
+            let i = index(nx, ny);
+            if !visited[i] && unsafe { image.unsafe_get_pixel(nx, ny) } == target {
+                visited[i] = true;
+                stack.push((nx, ny));
+            }
+        }
+    }
+
+    Some(Rect::new(
+        min_x as i32,
+        min_y as i32,
+        max_x - min_x + 1,
+        max_y - min_y + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma};
+
+    use super::*;
+
+    #[test]
+    fn fill_overhanging_rect() {
+        let mut image = GrayImage::new(4, 3);
+
+        assert_eq!(fill_rect(&mut image, Rect::new(2, 1, 5, 5), Luma([7])), 4);
+        #[rustfmt::skip]
+        assert_eq!(image.as_raw(), &[
+            0, 0, 0, 0,
+            0, 0, 7, 7,
+            0, 0, 7, 7,
+        ]);
+
+        assert_eq!(fill_rect(&mut image, Rect::new(4, 0, 1, 1), Luma([9])), 0);
+        assert_eq!(fill_rect(&mut image, Rect::new(-1, -1, 2, 9), Luma([1])), 3);
+        assert_eq!(image.as_raw(), &[1, 0, 0, 0, 1, 0, 7, 7, 1, 0, 7, 7]);
+    }
+
+    #[test]
+    fn rect_border_leaves_interior() {
+        let mut image = GrayImage::new(5, 5);
+        draw_rect_border(&mut image, Rect::new(1, 1, 3, 3), Luma([1]));
+
+        #[rustfmt::skip]
+        assert_eq!(image.as_raw(), &[
+            0, 0, 0, 0, 0,
+            0, 1, 1, 1, 0,
+            0, 1, 0, 1, 0,
+            0, 1, 1, 1, 0,
+            0, 0, 0, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn rect_border_is_clipped() {
+        let mut image = GrayImage::new(4, 3);
+        draw_rect_border(&mut image, Rect::new(-1, 1, 3, 5), Luma([1]));
+
+        #[rustfmt::skip]
+        assert_eq!(image.as_raw(), &[
+            0, 0, 0, 0,
+            1, 1, 0, 0,
+            0, 1, 0, 0,
+        ]);
+
+        draw_rect_border(&mut image, Rect::new(4, 0, 2, 2), Luma([2]));
+        draw_rect_border(&mut image, Rect::new(0, 0, 0, 2), Luma([2]));
+        assert!(image.pixels().all(|p| p[0] != 2));
+    }
+
+    #[test]
+    fn blit_clips_source() {
+        let src = GrayImage::from_vec(2, 2, vec![1, 2, 3, 4]).unwrap();
+        let mut image = GrayImage::new(3, 3);
+
+        assert!(blit_from(&mut image, &src, -1, -1));
+        assert_eq!(image.as_raw(), &[4, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert!(blit_from(&mut image, &src, 2, 1));
+        assert_eq!(image.as_raw(), &[4, 0, 0, 0, 0, 1, 0, 0, 3]);
+
+        assert!(!blit_from(&mut image, &src, 3, 0));
+        assert!(!blit_from(&mut image, &src, -2, 0));
+        assert!(!blit_from(&mut image, &GrayImage::new(0, 0), 0, 0));
+    }
+
+    #[test]
+    fn apply_checkerboard_mask_to_clipped_region() {
+        let mut image = GrayImage::new(4, 3);
+        apply_mask(
+            &mut image,
+            Rect::new(1, -1, 5, 3),
+            Luma([1]),
+            Luma([2]),
+            |x, y| (x + y) % 2 == 0,
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(image.into_raw(), [
+            0, 2, 1, 2,
+            0, 1, 2, 1,
+            0, 0, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn flood_fill_bounded_region() {
+        #[rustfmt::skip]
+        let mut image = GrayImage::from_vec(5, 4, vec![
+            0, 0, 9, 0, 0,
+            0, 0, 9, 0, 0,
+            9, 9, 9, 0, 0,
+            0, 0, 0, 9, 0,
+        ]).unwrap();
+
+        let rect = flood_fill(&mut image, 1, 1, Luma([5]));
+        assert_eq!(rect, Some(Rect::new(0, 0, 2, 2)));
+        assert_eq!(&image.as_raw()[..5], [5, 5, 9, 0, 0]);
+        assert_eq!(&image.as_raw()[5..10], [5, 5, 9, 0, 0]);
+        assert_eq!(&image.as_raw()[15..], [0, 0, 0, 9, 0]);
+
+        let rect = flood_fill(&mut image, 4, 0, Luma([7]));
+        assert_eq!(rect, Some(Rect::new(3, 0, 2, 4)));
+        assert_eq!(image.get_pixel(4, 3).0, [7]);
+        assert_eq!(image.get_pixel(0, 3).0, [0]);
+
+        // filling with the same value keeps the region
+        let rect = flood_fill(&mut image, 2, 0, Luma([9]));
+        assert_eq!(rect, Some(Rect::new(0, 0, 3, 3)));
+
+        assert_eq!(flood_fill(&mut image, 5, 0, Luma([1])), None);
+    }
+}
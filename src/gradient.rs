@@ -0,0 +1,83 @@
+use image::{GenericImageView, Luma};
+
+use crate::{get_pixel_border, BorderMode};
+
+/// Returns the horizontal and vertical Sobel responses `(gx, gy)` at the given coordinates.
+///
+/// The 3x3 neighbourhood is sampled with the border mode, so edge pixels need no special-casing.
+///
+/// # Panics
+///
+/// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+pub fn sobel_at<I: GenericImageView<Pixel = Luma<u8>>>(
+    image: &I,
+    x: i32,
+    y: i32,
+    mode: BorderMode<Luma<u8>>,
+) -> (f32, f32) {
+    let at = |dx: i32, dy: i32| {
+        get_pixel_border(image, x.saturating_add(dx), y.saturating_add(dy), mode).0[0] as f32
+    };
+
+    let gx = (at(1, -1) + 2.0 * at(1, 0) + at(1, 1)) - (at(-1, -1) + 2.0 * at(-1, 0) + at(-1, 1));
+    let gy = (at(-1, 1) + 2.0 * at(0, 1) + at(1, 1)) - (at(-1, -1) + 2.0 * at(0, -1) + at(1, -1));
+    (gx, gy)
+}
+
+/// Returns the Sobel gradient magnitude `sqrt(gx^2 + gy^2)` at the given coordinates.
+///
+/// # Panics
+///
+/// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+pub fn gradient_magnitude_at<I: GenericImageView<Pixel = Luma<u8>>>(
+    image: &I,
+    x: i32,
+    y: i32,
+    mode: BorderMode<Luma<u8>>,
+) -> f32 {
+    let (gx, gy) = sobel_at(image, x, y, mode);
+    gx.hypot(gy)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GrayImage;
+
+    use super::*;
+
+    #[test]
+    fn sobel_on_vertical_edge() {
+        let image = GrayImage::from_fn(4, 4, |x, _| Luma([if x < 2 { 0 } else { 255 }]));
+
+        let (gx, gy) = sobel_at(&image, 1, 0, BorderMode::Clamp);
+        assert_eq!(gx, 4.0 * 255.0);
+        assert_eq!(gy, 0.0);
+        assert_eq!(gradient_magnitude_at(&image, 1, 0, BorderMode::Clamp), gx);
+
+        // flat area away from the edge
+        assert_eq!(sobel_at(&image, 3, 3, BorderMode::Reflect), (0.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn sobel_clamp_for_empty_image() {
+        let image = GrayImage::new(0, 0);
+        sobel_at(&image, 0, 0, BorderMode::Clamp);
+    }
+
+    #[test]
+    #[should_panic]
+    fn gradient_magnitude_reflect_for_empty_image() {
+        let image = GrayImage::new(0, 0);
+        gradient_magnitude_at(&image, 0, 0, BorderMode::Reflect);
+    }
+
+    #[test]
+    fn sobel_constant_for_empty_image() {
+        let image = GrayImage::new(0, 0);
+        assert_eq!(
+            sobel_at(&image, 0, 0, BorderMode::Constant(Luma([9]))),
+            (0.0, 0.0)
+        );
+    }
+}
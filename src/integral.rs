@@ -0,0 +1,72 @@
+use image::{GenericImageView, Luma};
+
+use crate::Rect;
+
+/// Returns the summed-area table of the grayscale image.
+///
+/// The table has `(width + 1) * (height + 1)` entries in row-major order, where the entry
+/// at `(x, y)` is the sum of all pixels in the rectangle `[0, x) x [0, y)`.
+/// The first row and column are therefore zero.
+pub fn integral_image<I: GenericImageView<Pixel = Luma<u8>>>(image: &I) -> Vec<u64> {
+    let (width, height) = image.dimensions();
+    let stride = width as usize + 1;
+    let mut sat = vec![0u64; stride * (height as usize + 1)];
+
+    for y in 0..height {
+        let mut row_sum = 0;
+        for x in 0..width {
+            row_sum += unsafe { image.unsafe_get_pixel(x, y) }.0[0] as u64;
+            let i = (y as usize + 1) * stride + x as usize + 1;
+            sat[i] = sat[i - stride] + row_sum;
+        }
+    }
+    sat
+}
+
+/// Returns the sum of the pixels in the rectangle clipped to the image,
+/// using the summed-area table of [`integral_image`] for an image of the given dimensions.
+///
+/// # Panics
+///
+/// Panics if the table is smaller than the dimensions require.
+pub fn box_sum(sat: &[u64], dimensions: (u32, u32), rect: Rect) -> u64 {
+    let Some(rect) = rect.clip(dimensions.0, dimensions.1) else {
+        return 0;
+    };
+
+    let stride = dimensions.0 as usize + 1;
+    let (x0, y0) = (rect.x as usize, rect.y as usize);
+    let (x1, y1) = (x0 + rect.width as usize, y0 + rect.height as usize);
+    sat[y1 * stride + x1] + sat[y0 * stride + x0] - sat[y0 * stride + x1] - sat[y1 * stride + x0]
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GrayImage;
+
+    use super::*;
+
+    #[test]
+    fn box_sum_matches_naive_sum() {
+        let image = GrayImage::from_fn(5, 4, |x, y| Luma([(x * 40 + y * 13) as u8]));
+        let sat = integral_image(&image);
+        let dimensions = image.dimensions();
+        assert_eq!(sat.len(), 6 * 5);
+
+        let naive = |rect: Rect| -> u64 {
+            crate::pixels_in_rect(&image, rect)
+                .map(|(_, p)| p.0[0] as u64)
+                .sum()
+        };
+
+        for rect in [
+            Rect::new(0, 0, 5, 4),
+            Rect::new(1, 1, 2, 2),
+            Rect::new(-2, 3, 4, 9),
+            Rect::new(4, 0, 1, 1),
+        ] {
+            assert_eq!(box_sum(&sat, dimensions, rect), naive(rect));
+        }
+        assert_eq!(box_sum(&sat, dimensions, Rect::new(5, 0, 1, 1)), 0);
+    }
+}
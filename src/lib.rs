@@ -1,4 +1,123 @@
-use image::GenericImageView;
+mod border;
+mod bordered;
+mod bounds;
+mod color;
+mod compare;
+mod coordinate;
+mod fill;
+mod gradient;
+mod integral;
+mod math;
+#[cfg(feature = "rayon")]
+mod par;
+mod rect;
+mod region;
+mod remap;
+mod resize;
+mod sampler;
+mod search;
+mod transform;
+
+use std::ops::Deref;
+
+use image::{GenericImage, GenericImageView, ImageBuffer, Pixel};
+
+pub use border::{
+    for_each_neighbor, get_pixel_border, get_pixel_modes, get_pixel_wrapped_i64,
+    get_pixel_wrapped_mut, reflect_coordinate, wrap_coordinate, AxisBorderMode, BorderMode,
+};
+pub use bordered::{with_border, Bordered};
+pub use bounds::{classify_bounds, BoundsClass};
+pub use color::{
+    dither_to_luma, from_premultiplied, luminance_at, premultiply_at, sample_bilinear_srgb,
+    to_premultiplied, unpremultiply_at,
+};
+pub use compare::{abs_diff_at, ncc, ssd};
+pub use coordinate::{
+    chebyshev_distance, coordinate_to_offset, coords, get_pixel_offset, get_pixel_snapped,
+    manhattan_distance, offset_coordinate, offset_to_coordinate, valid_coords, windows, SnapPolicy,
+};
+pub use fill::{apply_mask, blit_from, draw_rect_border, fill_rect, flood_fill};
+pub use gradient::{gradient_magnitude_at, sobel_at};
+pub use integral::{box_sum, integral_image};
+pub use math::PixelAccumulator;
+#[cfg(feature = "rayon")]
+pub use par::{par_map_pixels_to, par_map_region};
+pub use rect::Rect;
+pub use region::{
+    area_average, border_pixels, clamp_pixel_in_rect, clamp_pixel_in_rect_checked, column_clamped,
+    get_pixel_context, map_region, min_max_in_rect, pixels_in_rect, pixels_in_rect_mut,
+    row_clamped, threshold_region, threshold_region_to, windows_1d_horizontal,
+};
+pub use remap::{map_coordinate, map_coordinate_clamped};
+pub use resize::{downsample, resize_bilinear, resize_nearest};
+pub use sampler::{gaussian_at, get_pixel_bilinear_mode, sample_line, Sampler};
+pub use search::{bounding_box, count_pixels, find_pixel, find_pixel_rev};
+pub use transform::{auto_crop, rotate90_cw, transpose};
+
+/// Image buffer with a `Vec` container, as returned by the functions producing new images.
+pub type PixelBuffer<P> = ImageBuffer<P, Vec<<P as Pixel>::Subpixel>>;
+
+/// Returns the width of the image as `i32`.
+#[inline]
+pub fn width_i32<I: GenericImageView>(image: &I) -> i32 {
+    let width = image.width();
+    debug_assert!(width <= i32::MAX as u32, "image width does not fit in i32");
+    width as i32
+}
+
+/// Returns the height of the image as `i32`.
+#[inline]
+pub fn height_i32<I: GenericImageView>(image: &I) -> i32 {
+    let height = image.height();
+    debug_assert!(
+        height <= i32::MAX as u32,
+        "image height does not fit in i32"
+    );
+    height as i32
+}
+
+/// Returns the dimensions of the image as `i32`.
+#[inline]
+pub fn dimensions_i32<I: GenericImageView>(image: &I) -> (i32, i32) {
+    (width_i32(image), height_i32(image))
+}
+
+/// Returns the number of pixels in the image as `usize`, or `None` if it does not fit,
+/// e.g. for large images on 32-bit targets.
+///
+/// Use it to size the allocations of per-pixel buffers.
+#[inline]
+pub fn checked_area_usize<I: GenericImageView>(image: &I) -> Option<usize> {
+    checked_area(image.dimensions())
+}
+
+/// Returns the product of the dimensions converted to `T`, or `None` if it does not fit.
+#[inline]
+fn checked_area<T: TryFrom<u64>>((width, height): (u32, u32)) -> Option<T> {
+    T::try_from(width as u64 * height as u64).ok()
+}
+
+/// Returns the coordinates of the bottom-right pixel of the image.
+///
+/// # Panics
+///
+/// Panics if the image is empty.
+#[inline]
+pub fn edges<I: GenericImageView>(image: &I) -> (u32, u32) {
+    let (width, height) = image.dimensions();
+    assert!(width > 0 && height > 0, "image is empty");
+    (width - 1, height - 1)
+}
+
+/// Returns the coordinates of the bottom-right pixel of the image, or `(0, 0)` if the image is empty.
+///
+/// Unlike [`edges`], it never panics, but the result is not a valid pixel of an empty image.
+#[inline]
+pub fn edges_saturating<I: GenericImageView>(image: &I) -> (u32, u32) {
+    let (width, height) = image.dimensions();
+    (width.saturating_sub(1), height.saturating_sub(1))
+}
 
 /// Returns `true` if the given coordinates are within the bounds of the image.
 #[inline]
@@ -12,6 +131,28 @@ pub fn get_pixel<I: GenericImageView>(image: &I, x: i32, y: i32) -> Option<I::Pi
     in_bounds(image, x, y).then(|| unsafe { image.unsafe_get_pixel(x as u32, y as u32) })
 }
 
+/// Returns a reference to the pixel of the image buffer at the given coordinates
+/// if it is within the bounds of the image.
+#[inline]
+pub fn get_pixel_ref<P, C>(image: &ImageBuffer<P, C>, x: i32, y: i32) -> Option<&P>
+where
+    P: Pixel,
+    C: Deref<Target = [P::Subpixel]>,
+{
+    in_bounds(image, x, y).then(|| image.get_pixel(x as u32, y as u32))
+}
+
+/// Returns the pixel at the given coordinates, without bounds checking.
+///
+/// # Safety
+///
+/// The coordinates must be within the bounds of the image, see [`in_bounds`].
+#[inline]
+pub unsafe fn get_pixel_unchecked<I: GenericImageView>(image: &I, x: i32, y: i32) -> I::Pixel {
+    debug_assert!(in_bounds(image, x, y), "pixel ({x}, {y}) is out of bounds");
+    image.unsafe_get_pixel(x as u32, y as u32)
+}
+
 /// Returns the pixel at the given coordinates, clamping the coordinates to the image bounds.
 #[inline]
 pub fn clamp_pixel<I: GenericImageView>(image: &I, x: i32, y: i32) -> I::Pixel {
@@ -23,21 +164,213 @@ pub fn clamp_pixel<I: GenericImageView>(image: &I, x: i32, y: i32) -> I::Pixel {
     }
 }
 
+/// Returns the coordinates clamped to the image bounds together with the pixel there,
+/// or `None` if the image is empty.
+#[inline]
+pub fn clamp_pixel_with_coord<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+) -> Option<((u32, u32), I::Pixel)> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let (x, y) = (
+        x.clamp(0, width_i32(image) - 1) as u32,
+        y.clamp(0, height_i32(image) - 1) as u32,
+    );
+    Some(((x, y), unsafe { image.unsafe_get_pixel(x, y) }))
+}
+
 /// Returns the pixel at the given coordinates, without checking for empty image.
+///
+/// # Safety
+///
+/// The image must not be empty.
 #[inline]
 pub unsafe fn clamp_pixel_unchecked<I: GenericImageView>(image: &I, x: i32, y: i32) -> I::Pixel {
+    debug_assert!(image.width() > 0 && image.height() > 0, "image is empty");
     image.unsafe_get_pixel(
         (x.max(0) as u32).min(image.width() - 1),
         (y.max(0) as u32).min(image.height() - 1),
     )
 }
 
+/// Returns the pixel at the given coordinates, clamping the coordinates to the precomputed
+/// [`edges`] of the image.
+///
+/// This lets callers sampling repeatedly hoist the dimensions lookup out of the loop.
+///
+/// # Safety
+///
+/// The `edges` must be within the bounds of the image, e.g. the result of [`edges`].
+#[inline]
+pub unsafe fn clamp_pixel_with_edges<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+    edges: (u32, u32),
+) -> I::Pixel {
+    debug_assert!(
+        edges.0 < image.width() && edges.1 < image.height(),
+        "edges are out of bounds"
+    );
+    image.unsafe_get_pixel(
+        (x.max(0) as u32).min(edges.0),
+        (y.max(0) as u32).min(edges.1),
+    )
+}
+
+/// Returns the corner pixels of the image in top-left, top-right, bottom-left, bottom-right order,
+/// or `None` if the image is empty.
+#[inline]
+pub fn corners<I: GenericImageView>(image: &I) -> Option<[I::Pixel; 4]> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let (r, b) = (width - 1, height - 1);
+    Some([(0, 0), (r, 0), (0, b), (r, b)].map(|(x, y)| unsafe { image.unsafe_get_pixel(x, y) }))
+}
+
+/// Returns an iterator over the pixels at the given coordinates, yielding `None` for coordinates
+/// out of bounds, see [`get_pixel`].
+#[inline]
+pub fn pixels_at<'a, I, It>(image: &'a I, coords: It) -> impl Iterator<Item = Option<I::Pixel>> + 'a
+where
+    I: GenericImageView,
+    It: IntoIterator<Item = (i32, i32)>,
+    It::IntoIter: 'a,
+{
+    coords.into_iter().map(|(x, y)| get_pixel(image, x, y))
+}
+
+/// Returns an iterator over the pixels at the given coordinates clamped to the image bounds,
+/// see [`clamp_pixel`].
+///
+/// # Panics
+///
+/// The iterator panics if the image is empty.
+#[inline]
+pub fn pixels_at_clamped<'a, I, It>(image: &'a I, coords: It) -> impl Iterator<Item = I::Pixel> + 'a
+where
+    I: GenericImageView,
+    It: IntoIterator<Item = (i32, i32)>,
+    It::IntoIter: 'a,
+{
+    coords.into_iter().map(|(x, y)| clamp_pixel(image, x, y))
+}
+
+/// Returns the channel values of the pixel at the given coordinates if it is within the bounds of the image.
+#[inline]
+pub fn channels_at<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+) -> Option<Vec<<I::Pixel as Pixel>::Subpixel>> {
+    get_pixel(image, x, y).map(|pixel| pixel.channels().to_vec())
+}
+
+/// Copies the channel values of the pixel at the given coordinates into `out`
+/// and returns the number of channels written.
+///
+/// Returns `None` if the coordinates are out of bounds or `out` is shorter than the channel count.
+#[inline]
+pub fn channels_at_into<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y: i32,
+    out: &mut [<I::Pixel as Pixel>::Subpixel],
+) -> Option<usize> {
+    let pixel = get_pixel(image, x, y)?;
+    let channels = pixel.channels();
+    out.get_mut(..channels.len())?.copy_from_slice(channels);
+    Some(channels.len())
+}
+
+/// Sets the pixel at the given coordinates.
+///
+/// Returns `false` and leaves the image unchanged if the coordinates are out of bounds.
+#[inline]
+pub fn set_pixel<I: GenericImage>(image: &mut I, x: i32, y: i32, pixel: I::Pixel) -> bool {
+    if !in_bounds(image, x, y) {
+        return false;
+    }
+
+    unsafe { image.unsafe_put_pixel(x as u32, y as u32, pixel) };
+    true
+}
+
+/// Blends the pixel at the given coordinates with `src` by the `alpha` factor,
+/// computing `dst * (1 - alpha) + src * alpha` per channel.
+///
+/// The `alpha` is clamped to `[0, 1]` and the result is rounded for integer subpixels.
+/// Returns `false` if the coordinates are out of bounds.
+pub fn blend_pixel<I: GenericImage>(
+    image: &mut I,
+    x: i32,
+    y: i32,
+    src: I::Pixel,
+    alpha: f32,
+) -> bool {
+    let Some(dst) = get_pixel(image, x, y) else {
+        return false;
+    };
+
+    let alpha = alpha.clamp(0.0, 1.0);
+    let pixel = math::weighted_sum(&[dst, src], &[1.0 - alpha, alpha]);
+    unsafe { image.unsafe_put_pixel(x as u32, y as u32, pixel) };
+    true
+}
+
+/// Returns the bilinearly interpolated pixel at the given subpixel coordinates,
+/// clamping the four taps to the image bounds.
+///
+/// Pixel centers lie on integer coordinates. Interpolated channels of integer subpixels
+/// are rounded to the nearest value. Returns `None` for an empty image or non-finite coordinates.
+pub fn get_pixel_bilinear<I: GenericImageView>(image: &I, x: f32, y: f32) -> Option<I::Pixel> {
+    if image.width() == 0 || image.height() == 0 {
+        return None;
+    }
+
+    let (coords, weights) = math::bilinear_taps(x, y)?;
+    let taps = coords.map(|(x, y)| clamp_pixel(image, x, y));
+    Some(math::weighted_sum(&taps, &weights))
+}
+
 #[cfg(test)]
 mod tests {
-    use image::GrayImage;
+    use image::{GrayImage, RgbImage, RgbaImage};
 
     use super::*;
 
+    #[test]
+    fn dimensions_as_i32() {
+        let image = GrayImage::new(3, 5);
+
+        assert_eq!(width_i32(&image), image.width() as i32);
+        assert_eq!(height_i32(&image), image.height() as i32);
+        assert_eq!(dimensions_i32(&image), (3, 5));
+    }
+
+    #[test]
+    fn checked_area_overflow() {
+        assert_eq!(checked_area_usize(&GrayImage::new(3, 5)), Some(15));
+        assert_eq!(checked_area_usize(&GrayImage::new(0, 5)), Some(0));
+
+        // a `u32` target mimics `usize` on 32-bit platforms
+        assert_eq!(checked_area::<u32>((65536, 65535)), Some(65536 * 65535));
+        assert_eq!(checked_area::<u32>((65536, 65536)), None);
+        assert_eq!(checked_area::<u32>((u32::MAX, 1)), Some(u32::MAX));
+        assert_eq!(
+            checked_area::<u64>((u32::MAX, u32::MAX)),
+            Some(u32::MAX as u64 * u32::MAX as u64)
+        );
+    }
+
     #[test]
     fn in_bounds_for_empty_image() {
         let image = GrayImage::new(0, 0);
@@ -77,6 +410,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lookup_pixel_ref_for_image_buffer() {
+        let image = GrayImage::from_vec(2, 2, vec![32, 64, 128, 255]).unwrap();
+
+        let pixel = get_pixel_ref(&image, 1, 0).unwrap();
+        assert!(std::ptr::eq(pixel, image.get_pixel(1, 0)));
+        assert_eq!(pixel.0, [64]);
+
+        assert!(get_pixel_ref(&image, 2, 0).is_none());
+        assert!(get_pixel_ref(&image, 0, -1).is_none());
+    }
+
+    #[test]
+    fn lookup_pixel_unchecked_matches_checked() {
+        let image = GrayImage::from_vec(2, 2, vec![32, 64, 128, 255]).unwrap();
+
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let pixel = unsafe { get_pixel_unchecked(&image, x, y) };
+            assert_eq!(Some(pixel), get_pixel(&image, x, y));
+        }
+    }
+
     #[test]
     #[should_panic]
     fn clamp_pixel_for_empty_image() {
@@ -117,6 +472,25 @@ mod tests {
         assert_eq!(&clamp_pixel(&image, r, b), image.get_pixel(1, 1));
     }
 
+    #[test]
+    fn clamp_pixel_reports_coordinate() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+
+        assert_eq!(
+            clamp_pixel_with_coord(&image, 1, 1),
+            Some(((1, 1), [4].into()))
+        );
+        assert_eq!(
+            clamp_pixel_with_coord(&image, -5, 9),
+            Some(((0, 1), [3].into()))
+        );
+        assert_eq!(
+            clamp_pixel_with_coord(&image, i32::MAX, i32::MIN),
+            Some(((2, 0), [2].into()))
+        );
+        assert_eq!(clamp_pixel_with_coord(&GrayImage::new(0, 2), 0, 0), None);
+    }
+
     #[test]
     fn clamp_pixel_for_non_empty_image_unsafe() {
         let image = GrayImage::from_vec(2, 2, vec![32, 64, 128, 255]).unwrap();
@@ -154,4 +528,153 @@ mod tests {
             assert_eq!(&clamp_pixel_unchecked(&image, r, b), image.get_pixel(1, 1));
         }
     }
+
+    #[test]
+    fn bilinear_pixel_interpolates_between_taps() {
+        let image = GrayImage::from_vec(2, 2, vec![0, 100, 100, 200]).unwrap();
+
+        assert!(get_pixel_bilinear(&GrayImage::new(0, 0), 0.0, 0.0).is_none());
+        assert!(get_pixel_bilinear(&image, f32::NAN, 0.0).is_none());
+
+        assert_eq!(get_pixel_bilinear(&image, 0.0, 0.0), Some([0].into()));
+        assert_eq!(get_pixel_bilinear(&image, 0.5, 0.0), Some([50].into()));
+        assert_eq!(get_pixel_bilinear(&image, 0.5, 0.5), Some([100].into()));
+
+        // taps outside of the image are clamped
+        assert_eq!(get_pixel_bilinear(&image, -1.0, -1.0), Some([0].into()));
+        assert_eq!(get_pixel_bilinear(&image, 2.0, 2.0), Some([200].into()));
+    }
+
+    #[test]
+    fn edges_of_image() {
+        assert_eq!(edges(&GrayImage::new(3, 5)), (2, 4));
+        assert_eq!(edges(&GrayImage::new(1, 1)), (0, 0));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "image is empty")]
+    fn clamp_pixel_unchecked_of_empty_image_in_debug() {
+        unsafe { clamp_pixel_unchecked(&GrayImage::new(0, 2), 0, 0) };
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "out of bounds")]
+    fn get_pixel_unchecked_out_of_bounds_in_debug() {
+        unsafe { get_pixel_unchecked(&GrayImage::new(2, 2), 2, 0) };
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "edges are out of bounds")]
+    fn clamp_pixel_with_wrong_edges_in_debug() {
+        unsafe { clamp_pixel_with_edges(&GrayImage::new(2, 2), 0, 0, (2, 1)) };
+    }
+
+    #[test]
+    fn saturating_edges_of_image() {
+        assert_eq!(edges_saturating(&GrayImage::new(3, 5)), (2, 4));
+        assert_eq!(edges_saturating(&GrayImage::new(1, 1)), (0, 0));
+        assert_eq!(edges_saturating(&GrayImage::new(0, 0)), (0, 0));
+        assert_eq!(edges_saturating(&GrayImage::new(0, 3)), (0, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn edges_of_empty_image() {
+        edges(&GrayImage::new(0, 3));
+    }
+
+    #[test]
+    fn clamp_pixel_with_edges_matches_clamp_pixel() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+        let edges = edges(&image);
+
+        for y in -2..4 {
+            for x in -2..5 {
+                let pixel = unsafe { clamp_pixel_with_edges(&image, x, y, edges) };
+                assert_eq!(pixel, clamp_pixel(&image, x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn corners_of_image() {
+        let image = GrayImage::from_vec(2, 2, vec![32, 64, 128, 255]).unwrap();
+        let pixels = corners(&image).unwrap().map(|p| p.0[0]);
+        assert_eq!(pixels, [32, 64, 128, 255]);
+
+        assert!(corners(&GrayImage::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn pixels_at_mixed_coordinates() {
+        let image = GrayImage::from_vec(2, 2, vec![32, 64, 128, 255]).unwrap();
+        let coords = [(0, 0), (-1, 0), (1, 1), (2, 1), (0, 1)];
+
+        let pixels: Vec<_> = pixels_at(&image, coords)
+            .map(|p| p.map(|p| p.0[0]))
+            .collect();
+        assert_eq!(pixels, [Some(32), None, Some(255), None, Some(128)]);
+
+        let pixels: Vec<_> = pixels_at_clamped(&image, coords).map(|p| p.0[0]).collect();
+        assert_eq!(pixels, [32, 32, 255, 255, 128]);
+    }
+
+    #[test]
+    fn channels_at_for_rgb_image() {
+        let image = RgbImage::from_vec(2, 1, vec![1, 2, 3, 4, 5, 6]).unwrap();
+
+        assert_eq!(channels_at(&image, 1, 0), Some(vec![4, 5, 6]));
+        assert_eq!(channels_at(&image, 2, 0), None);
+        assert_eq!(channels_at(&image, -1, 0), None);
+
+        let mut out = [0; 4];
+        assert_eq!(channels_at_into(&image, 0, 0, &mut out), Some(3));
+        assert_eq!(out, [1, 2, 3, 0]);
+        assert_eq!(channels_at_into(&image, 0, 1, &mut out), None);
+        assert_eq!(channels_at_into(&image, 0, 0, &mut out[..2]), None);
+    }
+
+    #[test]
+    fn channels_at_for_rgba_image() {
+        let image = RgbaImage::from_fn(2, 2, |x, y| [x as u8, y as u8, 7, 255].into());
+
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let pixel = image.get_pixel(x as u32, y as u32);
+            assert_eq!(channels_at(&image, x, y).as_deref(), Some(&pixel.0[..]));
+        }
+    }
+
+    #[test]
+    fn set_pixel_in_bounds_only() {
+        let mut image = GrayImage::new(2, 2);
+
+        assert!(set_pixel(&mut image, 1, 0, [5].into()));
+        assert!(!set_pixel(&mut image, 2, 0, [7].into()));
+        assert!(!set_pixel(&mut image, 0, -1, [7].into()));
+        assert_eq!(image.as_raw(), &[0, 5, 0, 0]);
+    }
+
+    #[test]
+    fn blend_pixel_by_alpha() {
+        let mut image = GrayImage::from_pixel(2, 1, [100].into());
+
+        assert!(blend_pixel(&mut image, 0, 0, [200].into(), 0.0));
+        assert_eq!(image.get_pixel(0, 0).0, [100]);
+
+        assert!(blend_pixel(&mut image, 0, 0, [200].into(), 1.0));
+        assert_eq!(image.get_pixel(0, 0).0, [200]);
+
+        assert!(blend_pixel(&mut image, 1, 0, [200].into(), 0.25));
+        assert_eq!(image.get_pixel(1, 0).0, [125]);
+
+        // alpha is clamped
+        assert!(blend_pixel(&mut image, 1, 0, [0].into(), 2.0));
+        assert_eq!(image.get_pixel(1, 0).0, [0]);
+
+        assert!(!blend_pixel(&mut image, 2, 0, [0].into(), 1.0));
+        assert!(!blend_pixel(&mut image, -1, 0, [0].into(), 1.0));
+    }
 }
@@ -0,0 +1,194 @@
+use std::marker::PhantomData;
+
+use image::{Pixel, Primitive};
+use num_traits::NumCast;
+
+/// Returns `true` if the subpixel type cannot represent fractional values.
+#[inline]
+#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` requires Rust 1.82
+pub(crate) fn is_integral<T: Primitive>() -> bool {
+    <T as NumCast>::from(0.5f32).map_or(true, |half| half.is_zero())
+}
+
+/// Converts the subpixel value to `f32`.
+#[inline]
+pub(crate) fn to_f32<T: Primitive>(value: T) -> f32 {
+    value.to_f32().unwrap_or_default()
+}
+
+/// Converts the subpixel value to `f64`.
+#[inline]
+pub(crate) fn to_f64<T: Primitive>(value: T) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// Converts the `f32` value back to the subpixel type, see [`from_f64`].
+#[inline]
+pub(crate) fn from_f32<T: Primitive>(value: f32) -> T {
+    from_f64(value as f64)
+}
+
+/// Converts the `f64` value back to the subpixel type.
+///
+/// Integer subpixels are rounded to the nearest value and saturated to the type range,
+/// float subpixels are converted as is.
+#[inline]
+pub(crate) fn from_f64<T: Primitive>(value: f64) -> T {
+    if !is_integral::<T>() {
+        return <T as NumCast>::from(value).unwrap_or(T::DEFAULT_MIN_VALUE);
+    }
+
+    let value = value.round();
+    if value.is_nan() || value <= to_f64(T::DEFAULT_MIN_VALUE) {
+        T::DEFAULT_MIN_VALUE
+    } else if value >= to_f64(T::DEFAULT_MAX_VALUE) {
+        T::DEFAULT_MAX_VALUE
+    } else {
+        <T as NumCast>::from(value).unwrap_or(T::DEFAULT_MAX_VALUE)
+    }
+}
+
+/// Returns the pixel with every channel set to the sum divided by `count`,
+/// converted with [`from_f64`]. The channels are written into `pixel`.
+#[inline]
+pub(crate) fn mean_pixel<P: Pixel>(mut pixel: P, sums: &[f64], count: f64) -> P {
+    for (channel, sum) in pixel.channels_mut().iter_mut().zip(sums) {
+        *channel = from_f64(sum / count);
+    }
+    pixel
+}
+
+/// Four bilinear taps with their weights.
+pub(crate) type Taps<T> = ([T; 4], [f32; 4]);
+
+/// Returns the coordinates and weights of the four taps of the bilinear interpolation
+/// at the given point, or `None` for non-finite coordinates.
+///
+/// Pixel centers lie on integer coordinates.
+pub(crate) fn bilinear_taps(x: f32, y: f32) -> Option<Taps<(i32, i32)>> {
+    if !x.is_finite() || !y.is_finite() {
+        return None;
+    }
+
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let (x1, y1) = (x0.saturating_add(1), y0.saturating_add(1));
+
+    let coords = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)];
+    let weights = [
+        (1.0 - fx) * (1.0 - fy),
+        fx * (1.0 - fy),
+        (1.0 - fx) * fy,
+        fx * fy,
+    ];
+    Some((coords, weights))
+}
+
+/// Returns the per-channel weighted sum of the pixels converted back to the pixel type.
+pub(crate) fn weighted_sum<P: Pixel>(pixels: &[P], weights: &[f32]) -> P {
+    let mut result = pixels[0];
+    for (c, channel) in result.channels_mut().iter_mut().enumerate() {
+        let value = pixels
+            .iter()
+            .zip(weights)
+            .map(|(pixel, weight)| to_f32(pixel.channels()[c]) * weight)
+            .sum();
+        *channel = from_f32(value);
+    }
+    result
+}
+
+/// Per-channel weighted accumulator of pixels, e.g. for convolution kernels.
+///
+/// Channels are accumulated as `f32` and converted back to the subpixel type by
+/// [`PixelAccumulator::finish`], so intermediate sums may exceed the subpixel range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelAccumulator<P: Pixel> {
+    sums: Vec<f32>,
+    _pixel: PhantomData<P>,
+}
+
+impl<P: Pixel> Default for PixelAccumulator<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Pixel> PixelAccumulator<P> {
+    /// Creates the accumulator with all channels at zero.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            sums: vec![0.0; P::CHANNEL_COUNT as usize],
+            _pixel: PhantomData,
+        }
+    }
+
+    /// Adds the channels of the pixel multiplied by the weight.
+    #[inline]
+    pub fn add_weighted(&mut self, pixel: P, weight: f32) {
+        for (sum, &c) in self.sums.iter_mut().zip(pixel.channels()) {
+            *sum += to_f32(c) * weight;
+        }
+    }
+
+    /// Returns the accumulated pixel.
+    ///
+    /// Channels of integer subpixels are rounded to the nearest value and saturated
+    /// to the subpixel range. The weights are not normalized.
+    pub fn finish(self) -> P {
+        let channels: Vec<P::Subpixel> = self.sums.into_iter().map(from_f32).collect();
+        *P::from_slice(&channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Luma, Rgb};
+
+    use super::*;
+
+    #[test]
+    fn from_f32_rounds_and_saturates_integers() {
+        assert_eq!(from_f32::<u8>(127.5), 128);
+        assert_eq!(from_f32::<u8>(-3.0), 0);
+        assert_eq!(from_f32::<u8>(300.0), 255);
+        assert_eq!(from_f32::<u8>(f32::NAN), 0);
+        assert_eq!(from_f32::<u32>(u32::MAX as f32), u32::MAX);
+        assert_eq!(from_f32::<f32>(0.25), 0.25);
+    }
+
+    #[test]
+    fn accumulator_averages_with_equal_weights() {
+        let mut acc = PixelAccumulator::new();
+        acc.add_weighted(Rgb([10u8, 200, 255]), 0.5);
+        acc.add_weighted(Rgb([20u8, 101, 255]), 0.5);
+        assert_eq!(acc.finish(), Rgb([15, 151, 255]));
+
+        let mut acc = PixelAccumulator::default();
+        acc.add_weighted(Luma([200u8]), 1.0);
+        acc.add_weighted(Luma([100u8]), 1.0);
+        assert_eq!(acc.clone().finish(), Luma([255]));
+        acc.add_weighted(Luma([255u8]), -2.0);
+        assert_eq!(acc.finish(), Luma([0]));
+
+        assert_eq!(PixelAccumulator::<Luma<f32>>::new().finish(), Luma([0.0]));
+    }
+
+    #[test]
+    fn from_f64_keeps_wide_integers_exact() {
+        assert_eq!(from_f64::<u32>(u32::MAX as f64 - 2.0), u32::MAX - 2);
+        assert_eq!(from_f64::<u32>(u32::MAX as f64 + 1.0), u32::MAX);
+        assert_eq!(from_f64::<u64>(u64::MAX as f64), u64::MAX);
+        assert_eq!(from_f64::<i32>(-2.5), -3);
+        assert_eq!(from_f64::<f64>(0.1), 0.1);
+    }
+
+    #[test]
+    fn weighted_sum_of_two_pixels() {
+        let pixels = [Luma([0u8]), Luma([255u8])];
+        assert_eq!(weighted_sum(&pixels, &[0.5, 0.5]), Luma([128]));
+        assert_eq!(weighted_sum(&pixels, &[1.0, 0.0]), Luma([0]));
+    }
+}
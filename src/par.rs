@@ -0,0 +1,116 @@
+use std::ops::DerefMut;
+
+use image::{GenericImageView, ImageBuffer, Pixel};
+use rayon::prelude::*;
+
+use crate::{PixelBuffer, Rect};
+
+/// Maps every pixel of the image to a new pixel, processing rows in parallel.
+///
+/// The closure receives the coordinates and the source pixel. Since the image is shared
+/// between threads, the closure may also sample the neighbourhood, e.g. with [`crate::clamp_pixel`].
+pub fn par_map_pixels_to<I, Q, F>(image: &I, f: F) -> PixelBuffer<Q>
+where
+    I: GenericImageView + Sync,
+    Q: Pixel,
+    Q::Subpixel: Send + Sync,
+    F: Fn(u32, u32, I::Pixel) -> Q + Sync,
+{
+    let (width, height) = image.dimensions();
+    let channel_count = Q::CHANNEL_COUNT as usize;
+    let row_len = width as usize * channel_count;
+    let mut buffer = ImageBuffer::new(width, height);
+
+    if row_len > 0 {
+        buffer
+            .par_chunks_mut(row_len)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, out) in row.chunks_exact_mut(channel_count).enumerate() {
+                    let (x, y) = (x as u32, y as u32);
+                    let pixel = f(x, y, unsafe { image.unsafe_get_pixel(x, y) });
+                    out.copy_from_slice(pixel.channels());
+                }
+            });
+    }
+
+    buffer
+}
+
+/// Replaces every pixel of the rectangle clipped to the image with the result of the closure,
+/// processing rows in parallel, see [`crate::map_region`].
+///
+/// Each row of the buffer is written by a single thread, so the writes never alias.
+pub fn par_map_region<P, C, F>(image: &mut ImageBuffer<P, C>, rect: Rect, f: F)
+where
+    P: Pixel,
+    P::Subpixel: Send,
+    C: DerefMut<Target = [P::Subpixel]>,
+    F: Fn((u32, u32), P) -> P + Sync,
+{
+    let (width, height) = image.dimensions();
+    let Some(rect) = rect.clip(width, height) else {
+        return;
+    };
+
+    let channels = P::CHANNEL_COUNT as usize;
+    let stride = width as usize * channels;
+    let (x0, y0) = (rect.x as usize, rect.y as usize);
+    let rows = y0 * stride..(y0 + rect.height as usize) * stride;
+    let columns = x0 * channels..(x0 + rect.width as usize) * channels;
+
+    (**image)[rows]
+        .par_chunks_mut(stride)
+        .enumerate()
+        .for_each(|(dy, row)| {
+            let y = (y0 + dy) as u32;
+            for (dx, out) in row[columns.clone()].chunks_exact_mut(channels).enumerate() {
+                let pixel = P::from_slice_mut(out);
+                *pixel = f(((x0 + dx) as u32, y), *pixel);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma, Rgb};
+
+    use super::*;
+    use crate::clamp_pixel;
+
+    #[test]
+    fn par_map_region_matches_serial() {
+        let image = GrayImage::from_fn(9, 6, |x, y| Luma([(x * 31 + y * 17) as u8]));
+        let f = |(x, y): (u32, u32), pixel: Luma<u8>| Luma([pixel[0] ^ (x * 3 + y) as u8]);
+
+        for rect in [
+            Rect::new(2, 1, 5, 3),
+            Rect::new(-3, 4, 20, 20),
+            Rect::new(0, 0, 9, 6),
+            Rect::new(9, 0, 1, 1),
+        ] {
+            let mut parallel = image.clone();
+            par_map_region(&mut parallel, rect, f);
+
+            let mut serial = image.clone();
+            crate::map_region(&mut serial, rect, f);
+            assert_eq!(parallel, serial);
+        }
+    }
+
+    #[test]
+    fn par_map_pixels_matches_serial() {
+        let image = GrayImage::from_fn(7, 5, |x, y| Luma([(x * 31 + y * 17) as u8]));
+        let f = |x: u32, y: u32, pixel: Luma<u8>| {
+            let right = clamp_pixel(&image, x as i32 + 1, y as i32);
+            Rgb([pixel[0], right[0], (x + y) as u8])
+        };
+
+        let parallel = par_map_pixels_to(&image, f);
+        let serial = ImageBuffer::from_fn(7, 5, |x, y| f(x, y, *image.get_pixel(x, y)));
+        assert_eq!(parallel, serial);
+
+        let empty = par_map_pixels_to(&GrayImage::new(0, 3), |_, _, p| p);
+        assert_eq!(empty.dimensions(), (0, 3));
+    }
+}
@@ -0,0 +1,75 @@
+/// A rectangle in image coordinates, which may lie partially or fully outside of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Creates the rectangle from its top-left corner and size.
+    #[inline]
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns `true` if the rectangle has no area.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns the part of the rectangle within an image of the given dimensions,
+    /// or `None` if they do not overlap.
+    pub fn clip(&self, width: u32, height: u32) -> Option<Rect> {
+        let (x0, y0) = ((self.x as i64).max(0), (self.y as i64).max(0));
+        let x1 = (self.x as i64 + self.width as i64).min(width as i64);
+        let y1 = (self.y as i64 + self.height as i64).min(height as i64);
+
+        (x0 < x1 && y0 < y1)
+            .then(|| Rect::new(x0 as i32, y0 as i32, (x1 - x0) as u32, (y1 - y0) as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_rect_to_image() {
+        assert_eq!(
+            Rect::new(1, 1, 2, 2).clip(4, 4),
+            Some(Rect::new(1, 1, 2, 2))
+        );
+        assert_eq!(
+            Rect::new(-1, -2, 3, 4).clip(4, 4),
+            Some(Rect::new(0, 0, 2, 2))
+        );
+        assert_eq!(
+            Rect::new(3, 2, 5, 5).clip(4, 4),
+            Some(Rect::new(3, 2, 1, 2))
+        );
+
+        assert_eq!(Rect::new(4, 0, 1, 1).clip(4, 4), None);
+        assert_eq!(Rect::new(-1, 0, 1, 1).clip(4, 4), None);
+        assert_eq!(Rect::new(0, 0, 0, 1).clip(4, 4), None);
+        assert_eq!(Rect::new(0, 0, 1, 1).clip(0, 0), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rect_serde_round_trip() {
+        let rect = Rect::new(-1, 2, 3, 4);
+        let json = serde_json::to_string(&rect).unwrap();
+
+        assert_eq!(json, r#"{"x":-1,"y":2,"width":3,"height":4}"#);
+        assert_eq!(serde_json::from_str::<Rect>(&json).unwrap(), rect);
+    }
+}
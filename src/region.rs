@@ -0,0 +1,610 @@
+use std::ops::DerefMut;
+
+use image::{GenericImage, GenericImageView, GrayImage, ImageBuffer, Luma, Pixel, Primitive};
+
+use crate::color::luminance;
+use crate::math::{mean_pixel, to_f64};
+use crate::{edges, BorderMode, Rect};
+
+/// Returns an iterator over the coordinates and pixels of the rectangle clipped to the image,
+/// in row-major order.
+pub fn pixels_in_rect<I: GenericImageView>(
+    image: &I,
+    rect: Rect,
+) -> impl Iterator<Item = ((u32, u32), I::Pixel)> + '_ {
+    let (width, height) = image.dimensions();
+    rect.clip(width, height).into_iter().flat_map(move |rect| {
+        let (x0, y0) = (rect.x as u32, rect.y as u32);
+        (y0..y0 + rect.height).flat_map(move |y| {
+            (x0..x0 + rect.width).map(move |x| ((x, y), unsafe { image.unsafe_get_pixel(x, y) }))
+        })
+    })
+}
+
+/// Returns an iterator over the coordinates and mutable pixels of the rectangle clipped to
+/// the image buffer, in row-major order.
+///
+/// The pixels are borrowed from disjoint parts of the underlying rows, so they never alias.
+pub fn pixels_in_rect_mut<P, C>(
+    image: &mut ImageBuffer<P, C>,
+    rect: Rect,
+) -> impl Iterator<Item = ((u32, u32), &mut P)> + '_
+where
+    P: Pixel,
+    C: DerefMut<Target = [P::Subpixel]>,
+{
+    let (width, height) = image.dimensions();
+    let (x0, y0, w, h) = rect.clip(width, height).map_or((0, 0, 0, 0), |rect| {
+        (
+            rect.x as usize,
+            rect.y as usize,
+            rect.width as usize,
+            rect.height as usize,
+        )
+    });
+    let channels = P::CHANNEL_COUNT as usize;
+    let stride = (width as usize * channels).max(1);
+
+    image
+        .chunks_exact_mut(stride)
+        .skip(y0)
+        .take(h)
+        .enumerate()
+        .flat_map(move |(dy, row)| {
+            row[x0 * channels..(x0 + w) * channels]
+                .chunks_exact_mut(channels)
+                .enumerate()
+                .map(move |(dx, pixel)| {
+                    let coords = ((x0 + dx) as u32, (y0 + dy) as u32);
+                    (coords, P::from_slice_mut(pixel))
+                })
+        })
+}
+
+/// Returns the pixel at the given coordinates clamped into the rectangle clipped to the image,
+/// or `None` if the rectangle does not overlap the image.
+#[inline]
+pub fn clamp_pixel_in_rect_checked<I: GenericImageView>(
+    image: &I,
+    rect: Rect,
+    x: i32,
+    y: i32,
+) -> Option<I::Pixel> {
+    let (width, height) = image.dimensions();
+    let rect = rect.clip(width, height)?;
+
+    let x = x.clamp(rect.x, rect.x + rect.width as i32 - 1);
+    let y = y.clamp(rect.y, rect.y + rect.height as i32 - 1);
+    Some(unsafe { image.unsafe_get_pixel(x as u32, y as u32) })
+}
+
+/// Returns the pixel at the given coordinates clamped into the rectangle clipped to the image.
+///
+/// # Panics
+///
+/// Panics if the rectangle does not overlap the image, see [`clamp_pixel_in_rect_checked`].
+#[inline]
+pub fn clamp_pixel_in_rect<I: GenericImageView>(image: &I, rect: Rect, x: i32, y: i32) -> I::Pixel {
+    clamp_pixel_in_rect_checked(image, rect, x, y).expect("rectangle does not overlap the image")
+}
+
+/// Returns an iterator over the coordinates and pixels of the outermost ring of the image,
+/// visiting each pixel once.
+///
+/// The ring is walked clockwise from the top-left corner: the top row, the right column,
+/// the bottom row and the left column.
+pub fn border_pixels<I: GenericImageView>(
+    image: &I,
+) -> impl Iterator<Item = (u32, u32, I::Pixel)> + '_ {
+    let (width, height) = image.dimensions();
+    let (width, height) = if width == 0 || height == 0 {
+        (0, 0)
+    } else {
+        (width, height)
+    };
+    let (right, bottom) = (width.saturating_sub(1), height.saturating_sub(1));
+
+    let top = (0..width).map(|x| (x, 0));
+    let right_column = (1..height).map(move |y| (right, y));
+    let bottom_row = (0..if height > 1 { right } else { 0 })
+        .rev()
+        .map(move |x| (x, bottom));
+    let left_column = (1..if width > 1 { bottom } else { 1 })
+        .rev()
+        .map(|y| (0, y));
+
+    top.chain(right_column)
+        .chain(bottom_row)
+        .chain(left_column)
+        .map(move |(x, y)| (x, y, unsafe { image.unsafe_get_pixel(x, y) }))
+}
+
+/// Returns the pixel at the coordinates relative to the top-left corner of the crop rectangle,
+/// looked up in the parent image.
+///
+/// Coordinates outside of the crop still read the parent pixels, so tiles can sample a halo
+/// of context around them. Only the coordinates outside of the parent are resolved with the
+/// border mode.
+///
+/// # Panics
+///
+/// Panics if the parent is empty, unless the mode is [`BorderMode::Constant`].
+pub fn get_pixel_context<I: GenericImageView>(
+    parent: &I,
+    crop: Rect,
+    x: i32,
+    y: i32,
+    mode: BorderMode<I::Pixel>,
+) -> I::Pixel {
+    let x = crop.x as i64 + x as i64;
+    let y = crop.y as i64 + y as i64;
+    match mode.resolve(x, y, parent.dimensions()) {
+        Ok((x, y)) => unsafe { parent.unsafe_get_pixel(x, y) },
+        Err(pixel) => pixel,
+    }
+}
+
+/// Returns `len` pixels of row `y` starting at `x_start`, clamping the out-of-range
+/// coordinates to the image bounds.
+///
+/// The row `y` itself is clamped to a valid row, so the result is suitable as the input
+/// of a horizontal separable filter near the image edges.
+///
+/// # Panics
+///
+/// Panics if the image is empty and `len` is not zero.
+pub fn row_clamped<I: GenericImageView>(
+    image: &I,
+    y: i32,
+    x_start: i32,
+    len: u32,
+) -> Vec<I::Pixel> {
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let (right, bottom) = edges(image);
+    let y = clamp_offset(y, 0, bottom);
+    (0..len)
+        .map(|i| unsafe { image.unsafe_get_pixel(clamp_offset(x_start, i, right), y) })
+        .collect()
+}
+
+/// Returns an iterator over the horizontal windows of `window` pixels along row `y`, one for each
+/// column of the image, clamping the out-of-range coordinates as [`row_clamped`].
+///
+/// The window of column `x` starts at `x - window / 2`, so odd windows are centered on `x` and even
+/// windows have one more pixel to the left. The iterator is empty for an empty image.
+pub fn windows_1d_horizontal<I: GenericImageView>(
+    image: &I,
+    y: u32,
+    window: u32,
+) -> impl Iterator<Item = Vec<I::Pixel>> + '_ {
+    let (width, height) = image.dimensions();
+    let columns = if height == 0 { 0 } else { width };
+    let y = y.min(height.saturating_sub(1)) as i32;
+    let half = (window / 2) as i64;
+
+    (0..columns).map(move |x| {
+        let x_start = (x as i64 - half).clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        row_clamped(image, y, x_start, window)
+    })
+}
+
+/// Returns `len` pixels of column `x` starting at `y_start`, clamping the out-of-range
+/// coordinates to the image bounds.
+///
+/// The column `x` itself is clamped to a valid column, see [`row_clamped`].
+///
+/// # Panics
+///
+/// Panics if the image is empty and `len` is not zero.
+pub fn column_clamped<I: GenericImageView>(
+    image: &I,
+    x: i32,
+    y_start: i32,
+    len: u32,
+) -> Vec<I::Pixel> {
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let (right, bottom) = edges(image);
+    let x = clamp_offset(x, 0, right);
+    (0..len)
+        .map(|i| unsafe { image.unsafe_get_pixel(x, clamp_offset(y_start, i, bottom)) })
+        .collect()
+}
+
+/// Returns `start + offset` clamped to `[0, edge]` without overflowing.
+#[inline]
+fn clamp_offset(start: i32, offset: u32, edge: u32) -> u32 {
+    (start as i64 + offset as i64).clamp(0, edge as i64) as u32
+}
+
+/// Returns the per-channel average of the pixels in the rectangle clipped to the image,
+/// or `None` if the rectangle does not overlap the image.
+///
+/// The averages of integer subpixels are rounded to the nearest value.
+pub fn area_average<I: GenericImageView>(image: &I, rect: Rect) -> Option<I::Pixel> {
+    let mut pixels = pixels_in_rect(image, rect);
+    let (_, first) = pixels.next()?;
+
+    let mut sums: Vec<f64> = first.channels().iter().map(|&c| to_f64(c)).collect();
+    let mut count = 1.0;
+    for (_, pixel) in pixels {
+        for (sum, &c) in sums.iter_mut().zip(pixel.channels()) {
+            *sum += to_f64(c);
+        }
+        count += 1.0;
+    }
+
+    Some(mean_pixel(first, &sums, count))
+}
+
+/// Replaces every pixel of the rectangle clipped to the image with the result of the closure
+/// applied to its coordinates and the pixel, in row-major order.
+pub fn map_region<I, F>(image: &mut I, rect: Rect, mut f: F)
+where
+    I: GenericImage,
+    F: FnMut((u32, u32), I::Pixel) -> I::Pixel,
+{
+    let (width, height) = image.dimensions();
+    let Some(rect) = rect.clip(width, height) else {
+        return;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        for x in x0..x0 + rect.width {
+            let pixel = f((x, y), unsafe { image.unsafe_get_pixel(x, y) });
+            unsafe { image.unsafe_put_pixel(x, y, pixel) };
+        }
+    }
+}
+
+/// Returns the minimum and maximum values of the grayscale pixels in the rectangle clipped
+/// to the image, or `None` if the rectangle does not overlap the image.
+pub fn min_max_in_rect<I: GenericImageView<Pixel = Luma<u8>>>(
+    image: &I,
+    rect: Rect,
+) -> Option<(u8, u8)> {
+    pixels_in_rect(image, rect).fold(None, |acc, (_, Luma([value]))| {
+        let (min, max) = acc.unwrap_or((value, value));
+        Some((min.min(value), max.max(value)))
+    })
+}
+
+/// Returns the binary mask of the rectangle clipped to the image, where pixels with
+/// a luminance of at least `threshold` are `255` and the rest are `0`.
+///
+/// The mask has the dimensions of the clipped rectangle and is empty if the rectangle
+/// does not overlap the image. See [`crate::luminance_at`] for the luminance range.
+pub fn threshold_region<I: GenericImageView>(image: &I, rect: Rect, threshold: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let Some(clipped) = rect.clip(width, height) else {
+        return GrayImage::new(0, 0);
+    };
+
+    let mask = pixels_in_rect(image, clipped)
+        .map(|(_, pixel)| {
+            if luminance(pixel) >= threshold {
+                255
+            } else {
+                0
+            }
+        })
+        .collect();
+    GrayImage::from_raw(clipped.width, clipped.height, mask).unwrap()
+}
+
+/// Binarizes the rectangle of the grayscale image clipped to its bounds in place,
+/// setting pixels with a value of at least `threshold` to `high` and the rest to `low`.
+pub fn threshold_region_to<I, T>(
+    image: &mut I,
+    rect: Rect,
+    threshold: T,
+    high: Luma<T>,
+    low: Luma<T>,
+) where
+    I: GenericImage<Pixel = Luma<T>>,
+    T: Primitive,
+{
+    let (width, height) = image.dimensions();
+    let Some(rect) = rect.clip(width, height) else {
+        return;
+    };
+
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    for y in y0..y0 + rect.height {
+        for x in x0..x0 + rect.width {
+            let Luma([value]) = unsafe { image.unsafe_get_pixel(x, y) };
+            let pixel = if value >= threshold { high } else { low };
+            unsafe { image.unsafe_put_pixel(x, y, pixel) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, RgbImage};
+
+    use super::*;
+
+    #[test]
+    fn context_reaches_into_parent() {
+        let parent = GrayImage::from_fn(4, 4, |x, y| [(y * 4 + x) as u8].into());
+        let crop = Rect::new(1, 1, 2, 2);
+        let border = BorderMode::Constant([255].into());
+
+        assert_eq!(get_pixel_context(&parent, crop, 0, 0, border), [5].into());
+        assert_eq!(get_pixel_context(&parent, crop, -1, -1, border), [0].into());
+        assert_eq!(get_pixel_context(&parent, crop, 2, 0, border), [7].into());
+        assert_eq!(
+            get_pixel_context(&parent, crop, -2, 0, border),
+            [255].into()
+        );
+        assert_eq!(
+            get_pixel_context(&parent, crop, 3, 3, BorderMode::Clamp),
+            [15].into()
+        );
+    }
+
+    #[test]
+    fn map_region_is_clipped() {
+        let mut image = GrayImage::from_fn(3, 3, |x, y| [(y * 3 + x) as u8].into());
+        map_region(&mut image, Rect::new(1, -1, 5, 2), |(x, y), p| {
+            [p[0] + 10 * (x + y) as u8].into()
+        });
+
+        assert_eq!(image.as_raw(), &[0, 11, 22, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn min_max_of_ramp_region() {
+        let image = GrayImage::from_fn(8, 4, |x, y| [(x * 10 + y) as u8].into());
+
+        assert_eq!(
+            min_max_in_rect(&image, Rect::new(2, 1, 3, 2)),
+            Some((21, 42))
+        );
+        assert_eq!(
+            min_max_in_rect(&image, Rect::new(-5, -5, 6, 6)),
+            Some((0, 0))
+        );
+        assert_eq!(
+            min_max_in_rect(&image, Rect::new(6, 0, 10, 10)),
+            Some((60, 73))
+        );
+        assert_eq!(min_max_in_rect(&image, Rect::new(8, 0, 1, 1)), None);
+    }
+
+    #[test]
+    fn row_clamped_duplicates_edge_pixels() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+
+        let row: Vec<u8> = row_clamped(&image, 0, -2, 6).iter().map(|p| p[0]).collect();
+        assert_eq!(row, [0, 0, 0, 1, 2, 2]);
+
+        let row: Vec<u8> = row_clamped(&image, 9, 1, 3).iter().map(|p| p[0]).collect();
+        assert_eq!(row, [4, 5, 5]);
+
+        assert!(row_clamped(&GrayImage::new(0, 0), 0, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn horizontal_windows_are_clamped() {
+        let image = GrayImage::from_fn(4, 2, |x, y| [(y * 4 + x) as u8].into());
+
+        let windows: Vec<Vec<u8>> = windows_1d_horizontal(&image, 1, 3)
+            .map(|w| w.iter().map(|p| p[0]).collect())
+            .collect();
+        assert_eq!(
+            windows,
+            [[4, 4, 5], [4, 5, 6], [5, 6, 7], [6, 7, 7]].map(Vec::from)
+        );
+
+        let even: Vec<Vec<u8>> = windows_1d_horizontal(&image, 0, 2)
+            .map(|w| w.iter().map(|p| p[0]).collect())
+            .collect();
+        assert_eq!(even, [[0, 0], [0, 1], [1, 2], [2, 3]].map(Vec::from));
+
+        assert_eq!(
+            windows_1d_horizontal(&GrayImage::new(3, 0), 0, 3).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn column_clamped_duplicates_edge_pixels() {
+        let image = GrayImage::from_fn(2, 3, |x, y| [(y * 2 + x) as u8].into());
+
+        let column: Vec<u8> = column_clamped(&image, 1, -2, 6)
+            .iter()
+            .map(|p| p[0])
+            .collect();
+        assert_eq!(column, [1, 1, 1, 3, 5, 5]);
+
+        let column: Vec<u8> = column_clamped(&image, -4, 2, 2)
+            .iter()
+            .map(|p| p[0])
+            .collect();
+        assert_eq!(column, [4, 4]);
+    }
+
+    #[test]
+    fn pixels_in_rect_are_clipped() {
+        let image = GrayImage::from_fn(3, 3, |x, y| [(y * 3 + x) as u8].into());
+
+        let pixels: Vec<_> = pixels_in_rect(&image, Rect::new(1, -1, 5, 3))
+            .map(|((x, y), p)| (x, y, p.0[0]))
+            .collect();
+        assert_eq!(pixels, [(1, 0, 1), (2, 0, 2), (1, 1, 4), (2, 1, 5)]);
+
+        assert_eq!(pixels_in_rect(&image, Rect::new(3, 0, 1, 1)).count(), 0);
+    }
+
+    #[test]
+    fn pixels_in_rect_mut_updates_buffer() {
+        let mut image = GrayImage::new(4, 3);
+
+        let mut count = 0;
+        for ((x, y), pixel) in pixels_in_rect_mut(&mut image, Rect::new(2, 1, 5, 5)) {
+            pixel.0[0] = (y * 4 + x) as u8;
+            count += 1;
+        }
+        assert_eq!(count, 4);
+
+        #[rustfmt::skip]
+        assert_eq!(image.as_raw(), &[
+            0, 0, 0, 0,
+            0, 0, 6, 7,
+            0, 0, 10, 11,
+        ]);
+
+        assert_eq!(
+            pixels_in_rect_mut(&mut image, Rect::new(0, 3, 1, 1)).count(),
+            0
+        );
+        assert_eq!(
+            pixels_in_rect_mut(&mut GrayImage::new(0, 0), Rect::new(0, 0, 1, 1)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn clamp_pixel_to_interior_rect() {
+        let image = GrayImage::from_fn(4, 4, |x, y| [(y * 4 + x) as u8].into());
+        let rect = Rect::new(1, 1, 2, 2);
+        let clamp = |x, y| clamp_pixel_in_rect(&image, rect, x, y).0[0];
+
+        assert_eq!(clamp(-1, -1), 5);
+        assert_eq!(clamp(0, 3), 9);
+        assert_eq!(clamp(3, 0), 6);
+        assert_eq!(clamp(9, 9), 10);
+        assert_eq!(clamp(2, 1), 6);
+
+        // the rectangle is clipped to the image first
+        assert_eq!(
+            clamp_pixel_in_rect(&image, Rect::new(2, 2, 9, 9), 9, 9).0,
+            [15]
+        );
+        assert_eq!(
+            clamp_pixel_in_rect_checked(&image, Rect::new(4, 0, 1, 1), 0, 0),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_pixel_to_rect_outside_of_image() {
+        let image = GrayImage::new(4, 4);
+        clamp_pixel_in_rect(&image, Rect::new(-2, 0, 2, 2), 0, 0);
+    }
+
+    #[test]
+    fn border_pixels_ring() {
+        let image = GrayImage::from_fn(3, 3, |x, y| [(y * 3 + x) as u8].into());
+        let ring: Vec<_> = border_pixels(&image)
+            .map(|(x, y, p)| (x, y, p.0[0]))
+            .collect();
+
+        assert_eq!(ring.len(), 8);
+        assert_eq!(
+            ring,
+            [
+                (0, 0, 0),
+                (1, 0, 1),
+                (2, 0, 2),
+                (2, 1, 5),
+                (2, 2, 8),
+                (1, 2, 7),
+                (0, 2, 6),
+                (0, 1, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn border_pixels_of_thin_images() {
+        let coords = |w, h| -> Vec<_> {
+            border_pixels(&GrayImage::new(w, h))
+                .map(|(x, y, _)| (x, y))
+                .collect()
+        };
+
+        assert_eq!(coords(1, 3), [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(coords(3, 1), [(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(coords(1, 1), [(0, 0)]);
+        assert_eq!(coords(2, 2), [(0, 0), (1, 0), (1, 1), (0, 1)]);
+        assert_eq!(coords(0, 3), []);
+        assert_eq!(coords(3, 0), []);
+    }
+
+    #[test]
+    fn area_average_of_region() {
+        let image = GrayImage::from_fn(4, 2, |x, _| [if x < 2 { 10 } else { 20 }].into());
+
+        assert_eq!(
+            area_average(&image, Rect::new(0, 0, 2, 2)),
+            Some([10].into())
+        );
+        assert_eq!(
+            area_average(&image, Rect::new(-5, -5, 7, 7)),
+            Some([10].into())
+        );
+        assert_eq!(
+            area_average(&image, Rect::new(1, 0, 2, 2)),
+            Some([15].into())
+        );
+        assert_eq!(
+            area_average(&image, Rect::new(0, 0, 4, 2)),
+            Some([15].into())
+        );
+        assert_eq!(area_average(&image, Rect::new(4, 0, 2, 2)), None);
+    }
+
+    #[test]
+    fn area_average_of_wide_subpixels_is_exact() {
+        let image: ImageBuffer<Luma<u32>, _> =
+            ImageBuffer::from_vec(2, 1, vec![u32::MAX - 1, u32::MAX - 3]).unwrap();
+
+        assert_eq!(
+            area_average(&image, Rect::new(0, 0, 2, 1)),
+            Some(Luma([u32::MAX - 2]))
+        );
+    }
+
+    #[test]
+    fn threshold_region_mask() {
+        let image = RgbImage::from_fn(4, 3, |x, _| {
+            if x % 2 == 0 {
+                [20, 20, 20].into()
+            } else {
+                [220, 220, 220].into()
+            }
+        });
+
+        let mask = threshold_region(&image, Rect::new(1, 1, 5, 5), 128.0);
+        assert_eq!(mask.dimensions(), (3, 2));
+        assert_eq!(mask.into_raw(), [255, 0, 255, 255, 0, 255]);
+
+        let empty = threshold_region(&image, Rect::new(-3, 0, 2, 2), 128.0);
+        assert_eq!(empty.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn threshold_region_to_binarizes_ramp() {
+        let mut image = GrayImage::from_fn(6, 2, |x, _| [(x * 50) as u8].into());
+        threshold_region_to(
+            &mut image,
+            Rect::new(1, 0, 9, 1),
+            150,
+            Luma([255]),
+            Luma([0]),
+        );
+
+        assert_eq!(&image.as_raw()[..6], [0, 0, 0, 255, 255, 255]);
+        assert_eq!(&image.as_raw()[6..], [0, 50, 100, 150, 200, 250]);
+    }
+}
@@ -0,0 +1,56 @@
+/// Maps the continuous coordinates from an image of `from` dimensions to an image of `to` dimensions,
+/// scaling each axis by the ratio of the dimensions.
+///
+/// Pixel `(x, y)` covers `[x, x + 1) x [y, y + 1)`, so the image centers and edges map onto each other.
+/// The result is not finite if `from` is empty.
+#[inline]
+pub fn map_coordinate(coord: (f64, f64), from: (u32, u32), to: (u32, u32)) -> (f64, f64) {
+    (
+        coord.0 * to.0 as f64 / from.0 as f64,
+        coord.1 * to.1 as f64 / from.1 as f64,
+    )
+}
+
+/// Maps the continuous coordinates as [`map_coordinate`] and returns the indices of the pixel
+/// containing them, clamped to the bounds of the `to` image.
+///
+/// # Panics
+///
+/// Panics if `to` is empty.
+#[inline]
+pub fn map_coordinate_clamped(coord: (f64, f64), from: (u32, u32), to: (u32, u32)) -> (u32, u32) {
+    assert!(to.0 > 0 && to.1 > 0, "image is empty");
+
+    let (x, y) = map_coordinate(coord, from, to);
+    let clamp = |value: f64, len: u32| (value.floor().max(0.0) as u32).min(len - 1);
+    (clamp(x, to.0), clamp(y, to.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_stays_centered() {
+        for (from, to) in [((4, 2), (8, 8)), ((100, 50), (3, 7)), ((1, 1), (640, 480))] {
+            let center = (from.0 as f64 / 2.0, from.1 as f64 / 2.0);
+            assert_eq!(
+                map_coordinate(center, from, to),
+                (to.0 as f64 / 2.0, to.1 as f64 / 2.0)
+            );
+        }
+
+        assert_eq!(map_coordinate((0.0, 0.0), (4, 2), (8, 8)), (0.0, 0.0));
+        assert_eq!(map_coordinate((4.0, 2.0), (4, 2), (8, 8)), (8.0, 8.0));
+    }
+
+    #[test]
+    fn clamped_indices() {
+        assert_eq!(map_coordinate_clamped((1.5, 0.5), (4, 2), (8, 8)), (3, 2));
+        assert_eq!(map_coordinate_clamped((4.0, 2.0), (4, 2), (8, 8)), (7, 7));
+        assert_eq!(
+            map_coordinate_clamped((-1.0, f64::NAN), (4, 2), (8, 8)),
+            (0, 0)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use image::{GenericImageView, ImageBuffer, Pixel};
+
+use crate::math::{mean_pixel, to_f64};
+use crate::{clamp_pixel, get_pixel_bilinear, PixelBuffer, Rect};
+
+/// Returns the image resized to the given dimensions using nearest-neighbour sampling.
+///
+/// Each destination pixel takes the source pixel containing its mapped center
+/// `(dst + 0.5) * scale`. Zero new dimensions produce an empty buffer.
+///
+/// # Panics
+///
+/// Panics if the source image is empty and the new dimensions are not.
+pub fn resize_nearest<I: GenericImageView>(
+    image: &I,
+    new_w: u32,
+    new_h: u32,
+) -> PixelBuffer<I::Pixel> {
+    let scale_x = image.width() as f64 / new_w as f64;
+    let scale_y = image.height() as f64 / new_h as f64;
+
+    ImageBuffer::from_fn(new_w, new_h, |x, y| {
+        let src_x = ((x as f64 + 0.5) * scale_x) as i32;
+        let src_y = ((y as f64 + 0.5) * scale_y) as i32;
+        clamp_pixel(image, src_x, src_y)
+    })
+}
+
+/// Returns the image resized to the given dimensions using bilinear interpolation.
+///
+/// Each destination pixel is mapped to the source space with `(dst + 0.5) * scale - 0.5`
+/// and sampled with clamped taps. Interpolated channels of integer subpixels are rounded
+/// to the nearest value when converted back to the pixel type. Zero new dimensions
+/// produce an empty buffer.
+///
+/// # Panics
+///
+/// Panics if the source image is empty and the new dimensions are not.
+pub fn resize_bilinear<I: GenericImageView>(
+    image: &I,
+    new_w: u32,
+    new_h: u32,
+) -> PixelBuffer<I::Pixel> {
+    let scale_x = image.width() as f32 / new_w as f32;
+    let scale_y = image.height() as f32 / new_h as f32;
+
+    ImageBuffer::from_fn(new_w, new_h, |x, y| {
+        let src_x = (x as f32 + 0.5) * scale_x - 0.5;
+        let src_y = (y as f32 + 0.5) * scale_y - 0.5;
+        get_pixel_bilinear(image, src_x, src_y).expect("source image is empty")
+    })
+}
+
+/// Returns the image downsampled by the integer factor, averaging each `factor x factor` block.
+///
+/// The result has `ceil(width / factor) x ceil(height / factor)` pixels. Partial blocks at the
+/// right and bottom edges are completed with clamped pixels, repeating the edge.
+/// Averages of integer subpixels are rounded to the nearest value.
+///
+/// # Panics
+///
+/// Panics if `factor` is zero.
+pub fn downsample<I: GenericImageView>(image: &I, factor: u32) -> PixelBuffer<I::Pixel> {
+    assert!(factor > 0, "downsample factor must be at least 1");
+
+    let (width, height) = image.dimensions();
+    let count = factor as f64 * factor as f64;
+    let mut sums = vec![0.0f64; I::Pixel::CHANNEL_COUNT as usize];
+
+    ImageBuffer::from_fn(width.div_ceil(factor), height.div_ceil(factor), |x, y| {
+        let (x0, y0) = (x as u64 * factor as u64, y as u64 * factor as u64);
+        let block = Rect::new(x0 as i32, y0 as i32, factor, factor)
+            .clip(width, height)
+            .unwrap();
+        let (x0, y0) = (block.x as u32, block.y as u32);
+        let (x1, y1) = (x0 + block.width - 1, y0 + block.height - 1);
+
+        // clamping repeats the last column and row of a partial block
+        let weight = |v: u32, last: u32, len: u32| {
+            if v == last {
+                (factor - len + 1) as f64
+            } else {
+                1.0
+            }
+        };
+
+        sums.fill(0.0);
+        for y in y0..=y1 {
+            let wy = weight(y, y1, block.height);
+            for x in x0..=x1 {
+                let w = wy * weight(x, x1, block.width);
+                let pixel = unsafe { image.unsafe_get_pixel(x, y) };
+                for (sum, &c) in sums.iter_mut().zip(pixel.channels()) {
+                    *sum += to_f64(c) * w;
+                }
+            }
+        }
+
+        mean_pixel(unsafe { image.unsafe_get_pixel(x0, y0) }, &sums, count)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma};
+
+    use super::*;
+
+    #[test]
+    fn resize_to_same_size_is_identity() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8 * 40].into());
+
+        assert_eq!(resize_nearest(&image, 3, 2), image);
+        assert_eq!(resize_bilinear(&image, 3, 2), image);
+    }
+
+    #[test]
+    fn resize_to_zero_size_is_empty() {
+        let image = GrayImage::new(3, 2);
+
+        for (w, h) in [(0, 0), (0, 4), (4, 0)] {
+            assert_eq!(resize_nearest(&image, w, h).dimensions(), (w, h));
+            assert_eq!(resize_bilinear(&image, w, h).dimensions(), (w, h));
+        }
+        assert_eq!(
+            resize_nearest(&GrayImage::new(0, 0), 0, 0).dimensions(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn resize_nearest_upscale_replicates_pixels() {
+        let image = GrayImage::from_vec(2, 2, vec![1, 2, 3, 4]).unwrap();
+        let resized = resize_nearest(&image, 4, 4);
+
+        #[rustfmt::skip]
+        assert_eq!(resized.into_raw(), [
+            1, 1, 2, 2,
+            1, 1, 2, 2,
+            3, 3, 4, 4,
+            3, 3, 4, 4,
+        ]);
+    }
+
+    #[test]
+    fn resize_bilinear_of_flat_image_stays_flat() {
+        let image = GrayImage::from_pixel(3, 5, [77].into());
+
+        for (w, h) in [(1, 1), (2, 3), (6, 10), (7, 4)] {
+            let resized = resize_bilinear(&image, w, h);
+            assert_eq!(resized.dimensions(), (w, h));
+            assert!(resized.pixels().all(|p| p.0 == [77]));
+        }
+    }
+
+    #[test]
+    fn resize_bilinear_upscale_2x() {
+        let image = GrayImage::from_vec(2, 2, vec![0, 100, 100, 200]).unwrap();
+        let resized = resize_bilinear(&image, 4, 4);
+
+        // corners keep the source values
+        assert_eq!(resized.get_pixel(0, 0).0, [0]);
+        assert_eq!(resized.get_pixel(3, 3).0, [200]);
+
+        // interior blends the neighbouring source pixels
+        assert_eq!(resized.get_pixel(1, 0).0, [25]);
+        assert_eq!(resized.get_pixel(2, 0).0, [75]);
+        assert_eq!(resized.get_pixel(1, 1).0, [50]);
+        assert_eq!(resized.get_pixel(2, 2).0, [150]);
+    }
+
+    #[test]
+    fn downsample_averages_blocks() {
+        #[rustfmt::skip]
+        let image = GrayImage::from_vec(4, 4, vec![
+            0, 2, 10, 10,
+            4, 6, 10, 10,
+            1, 1, 100, 0,
+            1, 1, 0, 100,
+        ]).unwrap();
+
+        let downsampled = downsample(&image, 2);
+        assert_eq!(downsampled.dimensions(), (2, 2));
+        assert_eq!(downsampled.into_raw(), [3, 10, 1, 50]);
+
+        assert_eq!(downsample(&image, 1), image);
+    }
+
+    #[test]
+    fn downsample_partial_blocks_are_clamped() {
+        let image = GrayImage::from_vec(3, 1, vec![10, 20, 40]).unwrap();
+
+        let downsampled = downsample(&image, 2);
+        assert_eq!(downsampled.dimensions(), (2, 1));
+        assert_eq!(downsampled.into_raw(), [15, 40]);
+    }
+
+    #[test]
+    fn downsample_by_factor_larger_than_image() {
+        let image = GrayImage::from_pixel(3, 3, [255].into());
+        assert_eq!(downsample(&image, 5000).into_raw(), [255]);
+        assert_eq!(downsample(&image, u32::MAX).into_raw(), [255]);
+
+        // the last column is repeated to fill the block: [0, 100, 100, 100]
+        let image = GrayImage::from_vec(2, 1, vec![0, 100]).unwrap();
+        assert_eq!(downsample(&image, 4).into_raw(), [75]);
+        assert_eq!(downsample(&image, u32::MAX).into_raw(), [100]);
+    }
+
+    #[test]
+    fn downsample_wide_subpixels_exactly() {
+        let image: ImageBuffer<Luma<u32>, _> =
+            ImageBuffer::from_vec(2, 1, vec![u32::MAX - 1, u32::MAX - 3]).unwrap();
+        assert_eq!(downsample(&image, 2).into_raw(), [u32::MAX - 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn downsample_by_zero() {
+        downsample(&GrayImage::new(2, 2), 0);
+    }
+}
@@ -0,0 +1,365 @@
+use image::{GenericImageView, Pixel};
+
+use crate::math::{bilinear_taps, to_f32, weighted_sum, PixelAccumulator, Taps};
+use crate::{get_pixel_bilinear, BorderMode};
+
+/// Returns the bilinearly interpolated pixel at the given subpixel coordinates,
+/// resolving each of the four taps with the border mode.
+///
+/// Taps outside of the image contribute the pixel of [`BorderMode::Constant`].
+/// Returns `None` for an empty image or non-finite coordinates, see [`Sampler::bilinear`].
+#[inline]
+pub fn get_pixel_bilinear_mode<I: GenericImageView>(
+    image: &I,
+    x: f32,
+    y: f32,
+    mode: BorderMode<I::Pixel>,
+) -> Option<I::Pixel> {
+    Sampler::new(image, mode).bilinear(x, y)
+}
+
+/// Returns `n` bilinearly interpolated samples evenly spaced along the segment
+/// from `(x0, y0)` to `(x1, y1)`, both ends included.
+///
+/// Samples outside of `[0, width - 1] x [0, height - 1]` or with non-finite coordinates
+/// are `None`. A single sample is taken at the start of the segment.
+pub fn sample_line<I: GenericImageView>(
+    image: &I,
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    n: usize,
+) -> Vec<Option<I::Pixel>> {
+    let (max_x, max_y) = (image.width() as f32 - 1.0, image.height() as f32 - 1.0);
+    let step = if n > 1 { 1.0 / (n - 1) as f32 } else { 0.0 };
+
+    (0..n)
+        .map(|i| {
+            let t = i as f32 * step;
+            let (x, y) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+            if (0.0..=max_x).contains(&x) && (0.0..=max_y).contains(&y) {
+                get_pixel_bilinear(image, x, y)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Returns the pixel at the given subpixel coordinates convolved with a Gaussian kernel,
+/// resolving the taps outside of the image with the border mode.
+///
+/// The kernel covers the pixels within `ceil(3 * sigma)` of the point and is normalized to
+/// a unit sum. The radius is capped at the larger image dimension and at 256 pixels, so a huge
+/// `sigma` gives a nearly flat average of the capped window instead of an unbounded kernel.
+/// A non-positive `sigma` returns the nearest pixel. Returns `None` for an empty image,
+/// non-finite coordinates or non-finite `sigma`.
+pub fn gaussian_at<I: GenericImageView>(
+    image: &I,
+    x: f32,
+    y: f32,
+    sigma: f32,
+    mode: BorderMode<I::Pixel>,
+) -> Option<I::Pixel> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 || !x.is_finite() || !y.is_finite() || !sigma.is_finite() {
+        return None;
+    }
+
+    let sampler = Sampler::new(image, mode);
+    if sigma <= 0.0 {
+        return Some(sampler.sample(x.round() as i32, y.round() as i32));
+    }
+
+    let cap = (width.max(height) as i64).min(GAUSSIAN_MAX_RADIUS);
+    let radius = ((3.0 * sigma as f64).ceil() as i64).min(cap);
+    let weights = |center: f32| -> (i64, Vec<f32>) {
+        let base = center.floor();
+        let fract = (center - base) as f64;
+        let variance = sigma as f64 * sigma as f64;
+        // the nearest tap gets the weight 1, so the sum cannot underflow for tiny sigmas
+        let nearest = fract.min(1.0 - fract).powi(2);
+        let mut weights: Vec<f32> = (-radius..=radius + 1)
+            .map(|offset| {
+                let d = offset as f64 - fract;
+                (-(d * d - nearest) / (2.0 * variance)).exp() as f32
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        weights.iter_mut().for_each(|w| *w /= total);
+        ((base as i64).saturating_sub(radius), weights)
+    };
+    let (x0, x_weights) = weights(x);
+    let (y0, y_weights) = weights(y);
+
+    let mut acc = PixelAccumulator::new();
+    for (dy, wy) in (0..).zip(&y_weights) {
+        for (dx, wx) in (0..).zip(&x_weights) {
+            let pixel = sampler.sample_i64(x0.saturating_add(dx), y0.saturating_add(dy));
+            acc.add_weighted(pixel, wx * wy);
+        }
+    }
+    Some(acc.finish())
+}
+
+/// Upper bound of the kernel radius of [`gaussian_at`].
+const GAUSSIAN_MAX_RADIUS: i64 = 256;
+
+/// Reusable handle for sampling an image with a fixed border mode.
+///
+/// The image dimensions are cached on construction, so repeated sampling does not query them again.
+pub struct Sampler<'a, I: GenericImageView> {
+    image: &'a I,
+    mode: BorderMode<I::Pixel>,
+    dimensions: (u32, u32),
+}
+
+impl<I: GenericImageView> Clone for Sampler<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: GenericImageView> Copy for Sampler<'_, I> {}
+
+impl<'a, I: GenericImageView> Sampler<'a, I> {
+    /// Creates the sampler of the image with the border mode.
+    #[inline]
+    pub fn new(image: &'a I, mode: BorderMode<I::Pixel>) -> Self {
+        Self {
+            image,
+            mode,
+            dimensions: image.dimensions(),
+        }
+    }
+
+    /// Returns the sampled image.
+    #[inline]
+    pub fn image(&self) -> &'a I {
+        self.image
+    }
+
+    /// Returns the cached dimensions of the image.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    /// Returns the border mode used for out-of-bounds taps.
+    #[inline]
+    pub fn mode(&self) -> BorderMode<I::Pixel> {
+        self.mode
+    }
+
+    /// Returns the pixel at the given coordinates, see [`crate::get_pixel_border`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+    #[inline]
+    pub fn sample(&self, x: i32, y: i32) -> I::Pixel {
+        self.sample_i64(x as i64, y as i64)
+    }
+
+    #[inline]
+    fn sample_i64(&self, x: i64, y: i64) -> I::Pixel {
+        match self.mode.resolve(x, y, self.dimensions) {
+            Ok((x, y)) => unsafe { self.image.unsafe_get_pixel(x, y) },
+            Err(pixel) => pixel,
+        }
+    }
+
+    /// Appends the pixels at the given coordinates to `out`, see [`Sampler::sample`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image is empty, unless the mode is [`BorderMode::Constant`].
+    pub fn sample_batch(&self, coords: &[(i32, i32)], out: &mut Vec<I::Pixel>) {
+        out.reserve(coords.len());
+        out.extend(coords.iter().map(|&(x, y)| self.sample(x, y)));
+    }
+
+    /// Returns the bilinearly interpolated pixel at the given subpixel coordinates,
+    /// resolving the taps with the border mode.
+    ///
+    /// Interpolated channels of integer subpixels are rounded to the nearest value.
+    /// Returns `None` for an empty image or non-finite coordinates.
+    pub fn bilinear(&self, x: f32, y: f32) -> Option<I::Pixel> {
+        let (taps, weights) = self.bilinear_taps(x, y)?;
+        Some(weighted_sum(&taps, &weights))
+    }
+
+    /// Writes the bilinearly interpolated channels at the given subpixel coordinates into `out`
+    /// without rounding, and returns the number of channels written.
+    ///
+    /// At most `out.len()` channels are written. Returns `0` for an empty image or non-finite coordinates.
+    pub fn bilinear_into(&self, x: f32, y: f32, out: &mut [f32]) -> usize {
+        let Some((taps, weights)) = self.bilinear_taps(x, y) else {
+            return 0;
+        };
+
+        let count = out.len().min(I::Pixel::CHANNEL_COUNT as usize);
+        for (c, value) in out[..count].iter_mut().enumerate() {
+            *value = taps
+                .iter()
+                .zip(weights)
+                .map(|(tap, weight)| to_f32(tap.channels()[c]) * weight)
+                .sum();
+        }
+        count
+    }
+
+    fn bilinear_taps(&self, x: f32, y: f32) -> Option<Taps<I::Pixel>> {
+        if self.dimensions.0 == 0 || self.dimensions.1 == 0 {
+            return None;
+        }
+
+        let (coords, weights) = bilinear_taps(x, y)?;
+        Some((coords.map(|(x, y)| self.sample(x, y)), weights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, RgbImage};
+
+    use super::*;
+
+    #[test]
+    fn sample_line_through_ramp_is_monotonic() {
+        let image = GrayImage::from_fn(5, 3, |x, _| [(x * 50) as u8].into());
+        let profile = sample_line(&image, 0.0, 1.0, 4.0, 1.0, 9);
+
+        let values: Vec<u8> = profile.iter().map(|p| p.unwrap()[0]).collect();
+        assert_eq!(values, [0, 25, 50, 75, 100, 125, 150, 175, 200]);
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn sample_line_outside_is_none() {
+        let image = GrayImage::new(4, 4);
+        let profile = sample_line(&image, -1.0, 0.0, 5.0, 0.0, 7);
+
+        let inside: Vec<bool> = profile.iter().map(Option::is_some).collect();
+        assert_eq!(inside, [false, true, true, true, true, false, false]);
+        assert!(sample_line(&image, 0.0, 0.0, 3.0, 3.0, 0).is_empty());
+        assert_eq!(
+            sample_line(&image, 1.0, 1.0, 3.0, 3.0, 1),
+            [Some([0].into())]
+        );
+    }
+
+    #[test]
+    fn gaussian_blends_two_tones() {
+        let image = GrayImage::from_fn(8, 2, |x, _| [if x < 4 { 40 } else { 200 }].into());
+
+        let wide = gaussian_at(&image, 3.5, 0.5, 4.0, BorderMode::Clamp).unwrap();
+        assert!(wide[0] > 40 && wide[0] < 200, "{wide:?}");
+        assert_eq!(wide, [120].into());
+
+        let narrow = gaussian_at(&image, 1.0, 1.0, 0.1, BorderMode::Clamp).unwrap();
+        assert_eq!(narrow, [40].into());
+        assert_eq!(
+            gaussian_at(&image, 5.4, 0.0, 0.0, BorderMode::Clamp),
+            Some([200].into())
+        );
+
+        // the radius is capped at the image extent, so huge sigmas average the capped window
+        for sigma in [300.0, 1e9, f32::MAX] {
+            let huge = gaussian_at(&image, 3.5, 0.5, sigma, BorderMode::Clamp);
+            assert_eq!(huge, Some([120].into()));
+        }
+        // tiny sigmas pick the nearest pixel, or average the tied nearest pixels
+        let flat = GrayImage::from_pixel(4, 4, [200].into());
+        for sigma in [0.01, 1e-30, f32::MIN_POSITIVE] {
+            let half = gaussian_at(&flat, 1.5, 1.5, sigma, BorderMode::Clamp);
+            assert_eq!(half, Some([200].into()));
+            let whole = gaussian_at(&flat, 1.0, 2.0, sigma, BorderMode::Clamp);
+            assert_eq!(whole, Some([200].into()));
+        }
+        let tied = gaussian_at(&image, 3.5, 0.0, 0.01, BorderMode::Clamp);
+        assert_eq!(tied, Some([120].into()));
+        let nearest = gaussian_at(&image, 3.6, 0.0, 0.01, BorderMode::Clamp);
+        assert_eq!(nearest, Some([200].into()));
+
+        let far = gaussian_at(&image, 1e30, -1e30, 2.0, BorderMode::Clamp);
+        assert_eq!(far, Some([200].into()));
+
+        assert_eq!(
+            gaussian_at(&GrayImage::new(0, 0), 0.0, 0.0, 1.0, BorderMode::Clamp),
+            None
+        );
+        assert_eq!(
+            gaussian_at(&image, f32::NAN, 0.0, 1.0, BorderMode::Clamp),
+            None
+        );
+    }
+
+    #[test]
+    fn sampler_bilinear_with_border_mode() {
+        let image = GrayImage::from_vec(2, 1, vec![0, 100]).unwrap();
+
+        let clamp = Sampler::new(&image, BorderMode::Clamp);
+        assert_eq!(clamp.bilinear(1.5, 0.0), Some([100].into()));
+
+        let wrap = Sampler::new(&image, BorderMode::Wrap);
+        assert_eq!(wrap.bilinear(1.5, 0.0), Some([50].into()));
+
+        let empty = GrayImage::new(0, 0);
+        assert_eq!(
+            Sampler::new(&empty, BorderMode::Clamp).bilinear(0.0, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn bilinear_mode_wraps_taps() {
+        let image = GrayImage::from_vec(3, 1, vec![200, 100, 0]).unwrap();
+
+        let wrapped = get_pixel_bilinear_mode(&image, 2.5, 0.0, BorderMode::Wrap);
+        assert_eq!(wrapped, Some([100].into()));
+
+        let clamped = get_pixel_bilinear_mode(&image, 2.5, 0.0, BorderMode::Clamp);
+        assert_eq!(clamped, Some([0].into()));
+
+        let constant = get_pixel_bilinear_mode(&image, 2.5, 0.0, BorderMode::Constant([50].into()));
+        assert_eq!(constant, Some([25].into()));
+    }
+
+    #[test]
+    fn sampler_bilinear_into_rgb() {
+        let image = RgbImage::from_vec(2, 1, vec![0, 10, 255, 101, 20, 0]).unwrap();
+        let sampler = Sampler::new(&image, BorderMode::Clamp);
+
+        let mut out = [0.0; 4];
+        assert_eq!(sampler.bilinear_into(0.5, 0.0, &mut out), 3);
+        assert_eq!(out, [50.5, 15.0, 127.5, 0.0]);
+
+        let rounded = out[..3]
+            .iter()
+            .map(|&c| c.round() as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(rounded, sampler.bilinear(0.5, 0.0).unwrap().0);
+
+        assert_eq!(sampler.bilinear_into(f32::NAN, 0.0, &mut out), 0);
+    }
+
+    #[test]
+    fn sampler_batch_matches_single_samples() {
+        let image = GrayImage::from_fn(3, 2, |x, y| [(y * 3 + x) as u8].into());
+        let coords = [(0, 0), (-1, 5), (4, 1), (2, 1), (-7, -7)];
+
+        for mode in [BorderMode::Clamp, BorderMode::Wrap, BorderMode::Reflect] {
+            let sampler = Sampler::new(&image, mode);
+            let mut out = vec![];
+            sampler.sample_batch(&coords, &mut out);
+
+            let expected: Vec<_> = coords.iter().map(|&(x, y)| sampler.sample(x, y)).collect();
+            assert_eq!(out, expected);
+            for (&(x, y), pixel) in coords.iter().zip(out) {
+                assert_eq!(pixel, crate::get_pixel_border(&image, x, y, mode));
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use image::GenericImageView;
+
+use crate::Rect;
+
+/// Returns the number of pixels of the image satisfying the predicate.
+pub fn count_pixels<I, F>(image: &I, mut pred: F) -> u64
+where
+    I: GenericImageView,
+    F: FnMut(I::Pixel) -> bool,
+{
+    image.pixels().filter(|&(_, _, pixel)| pred(pixel)).count() as u64
+}
+
+/// Returns the coordinates of the first pixel in row-major order satisfying the predicate.
+pub fn find_pixel<I, F>(image: &I, mut pred: F) -> Option<(u32, u32)>
+where
+    I: GenericImageView,
+    F: FnMut(I::Pixel) -> bool,
+{
+    image
+        .pixels()
+        .find(|&(_, _, pixel)| pred(pixel))
+        .map(|(x, y, _)| (x, y))
+}
+
+/// Returns the coordinates of the last pixel in row-major order satisfying the predicate,
+/// scanning from the bottom-right corner.
+pub fn find_pixel_rev<I, F>(image: &I, mut pred: F) -> Option<(u32, u32)>
+where
+    I: GenericImageView,
+    F: FnMut(I::Pixel) -> bool,
+{
+    let (width, height) = image.dimensions();
+    (0..height)
+        .rev()
+        .flat_map(|y| (0..width).rev().map(move |x| (x, y)))
+        .find(|&(x, y)| pred(unsafe { image.unsafe_get_pixel(x, y) }))
+}
+
+/// Returns the tightest rectangle containing all pixels satisfying the predicate,
+/// or `None` if no pixel does.
+pub fn bounding_box<I, F>(image: &I, mut pred: F) -> Option<Rect>
+where
+    I: GenericImageView,
+    F: FnMut(I::Pixel) -> bool,
+{
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.pixels() {
+        if pred(pixel) {
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            });
+        }
+    }
+
+    bounds.map(|(x0, y0, x1, y1)| Rect::new(x0 as i32, y0 as i32, x1 - x0 + 1, y1 - y0 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, RgbaImage};
+
+    use super::*;
+
+    #[test]
+    fn count_pixels_equal_to_value() {
+        let image = GrayImage::from_vec(3, 2, vec![0, 7, 7, 1, 7, 0]).unwrap();
+
+        assert_eq!(count_pixels(&image, |p| p.0 == [7]), 3);
+        assert_eq!(count_pixels(&image, |p| p.0 == [0]), 2);
+        assert_eq!(count_pixels(&image, |p| p.0 == [9]), 0);
+        assert_eq!(count_pixels(&GrayImage::new(0, 0), |_| true), 0);
+    }
+
+    #[test]
+    fn find_marker_pixel() {
+        let mut image = GrayImage::new(4, 3);
+        image.put_pixel(2, 1, [255].into());
+
+        assert_eq!(find_pixel(&image, |p| p.0 == [255]), Some((2, 1)));
+        assert_eq!(find_pixel_rev(&image, |p| p.0 == [255]), Some((2, 1)));
+
+        assert_eq!(find_pixel(&image, |p| p.0 == [0]), Some((0, 0)));
+        assert_eq!(find_pixel_rev(&image, |p| p.0 == [0]), Some((3, 2)));
+
+        assert_eq!(find_pixel(&image, |p| p.0 == [1]), None);
+        assert_eq!(find_pixel_rev(&image, |p| p.0 == [1]), None);
+    }
+
+    #[test]
+    fn bounding_box_of_opaque_region() {
+        let mut image = RgbaImage::new(6, 5);
+        for (x, y) in [(2, 1), (4, 2), (3, 3)] {
+            image.put_pixel(x, y, [255, 0, 0, 255].into());
+        }
+
+        let opaque = |p: image::Rgba<u8>| p.0[3] > 0;
+        assert_eq!(bounding_box(&image, opaque), Some(Rect::new(2, 1, 3, 3)));
+        assert_eq!(bounding_box(&RgbaImage::new(3, 3), opaque), None);
+    }
+}
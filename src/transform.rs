@@ -0,0 +1,79 @@
+use image::{GenericImageView, ImageBuffer};
+
+use crate::{bounding_box, PixelBuffer};
+
+/// Returns the image transposed, swapping its rows and columns.
+pub fn transpose<I: GenericImageView>(image: &I) -> PixelBuffer<I::Pixel> {
+    let (width, height) = image.dimensions();
+    ImageBuffer::from_fn(height, width, |x, y| unsafe {
+        image.unsafe_get_pixel(y, x)
+    })
+}
+
+/// Returns the image rotated by 90 degrees clockwise.
+pub fn rotate90_cw<I: GenericImageView>(image: &I) -> PixelBuffer<I::Pixel> {
+    let (width, height) = image.dimensions();
+    ImageBuffer::from_fn(height, width, |x, y| unsafe {
+        image.unsafe_get_pixel(y, height - 1 - x)
+    })
+}
+
+/// Returns the copy of the image cropped to the [`bounding_box`] of the pixels satisfying
+/// the predicate, or `None` if no pixel does.
+pub fn auto_crop<I, F>(image: &I, pred: F) -> Option<PixelBuffer<I::Pixel>>
+where
+    I: GenericImageView,
+    F: FnMut(I::Pixel) -> bool,
+{
+    let rect = bounding_box(image, pred)?;
+    let (x0, y0) = (rect.x as u32, rect.y as u32);
+    Some(ImageBuffer::from_fn(
+        rect.width,
+        rect.height,
+        |x, y| unsafe { image.unsafe_get_pixel(x0 + x, y0 + y) },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{imageops, GrayImage};
+
+    use super::*;
+
+    #[test]
+    fn transpose_2x3() {
+        let image = GrayImage::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let transposed = transpose(&image);
+
+        assert_eq!(transposed.dimensions(), (3, 2));
+        assert_eq!(transposed.into_raw(), [1, 3, 5, 2, 4, 6]);
+    }
+
+    #[test]
+    fn rotate90_cw_matches_imageops() {
+        let image = GrayImage::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let rotated = rotate90_cw(&image);
+
+        assert_eq!(rotated.dimensions(), (3, 2));
+        assert_eq!(rotated.as_raw(), &[5, 3, 1, 6, 4, 2]);
+        assert_eq!(rotated, imageops::rotate90(&image));
+    }
+
+    #[test]
+    fn auto_crop_uniform_border() {
+        let image = GrayImage::from_fn(5, 4, |x, y| {
+            [if (1..4).contains(&x) && (1..3).contains(&y) {
+                (y * 5 + x) as u8
+            } else {
+                0
+            }]
+            .into()
+        });
+
+        let cropped = auto_crop(&image, |p| p.0 != [0]).unwrap();
+        assert_eq!(cropped.dimensions(), (3, 2));
+        assert_eq!(cropped.into_raw(), [6, 7, 8, 11, 12, 13]);
+
+        assert!(auto_crop(&GrayImage::new(3, 3), |p| p.0 != [0]).is_none());
+    }
+}